@@ -69,7 +69,109 @@ pub enum PresaleError {
     TokenTransferFailed,
 
     #[error("Already distributed 100% of tokens")]
-    AlreadyDistributedOverflow
+    AlreadyDistributedOverflow,
+
+    #[error("Presale account is already initialized")]
+    AlreadyInitialized,
+
+    #[error("Price is invalid")]
+    InvalidPrice,
+
+    #[error("Presale is still active")]
+    StillActive,
+
+    #[error("Nothing to claim")]
+    NothingToClaim,
+
+    #[error("Vesting schedule is invalid")]
+    InvalidVesting,
+
+    #[error("Sale window is invalid")]
+    InvalidSaleWindow,
+
+    #[error("Presale has ended")]
+    SaleEnded,
+
+    #[error("Math overflow")]
+    MathOverflow,
+
+    #[error("Round is invalid")]
+    InvalidRound,
+
+    #[error("Presale is already finalized")]
+    AlreadyFinalized,
+
+    #[error("Presale is not finalized yet")]
+    NotFinalized,
+
+    #[error("Presale did not reach the softcap")]
+    SaleFailed,
+
+    #[error("Presale did not fail, nothing to refund")]
+    NotRefundable,
+
+    #[error("Nothing to refund")]
+    NothingToRefund,
+
+    #[error("Whitelist proof is invalid")]
+    InvalidWhitelistProof,
+
+    #[error("You cant buy more than your personal allocation")]
+    PersonalCapExceeded,
+
+    #[error("Payment mint is invalid")]
+    InvalidPaymentMint,
+
+    #[error("Price feed is invalid")]
+    InvalidOracle,
+
+    #[error("Price feed is stale")]
+    StalePrice,
+
+    #[error("Price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+
+    #[error("You reached the maximum number of buys")]
+    TooManyBuys,
+
+    #[error("You have to wait more slots before buying again")]
+    BuyCooldown,
+
+    #[error("Too many buys in this slot")]
+    SlotBuyLimitReached,
+
+    #[error("No authority proposal is pending")]
+    NoPendingAuthority,
+
+    #[error("Multisig signers or threshold are invalid")]
+    InvalidMultisig,
+
+    #[error("Signer is not a member of the multisig")]
+    NotMultisigSigner,
+
+    #[error("Not enough multisig approvals")]
+    NotEnoughApprovals,
+
+    #[error("Proposal was already approved by this signer")]
+    AlreadyApproved,
+
+    #[error("Proposal was already executed")]
+    AlreadyExecuted,
+
+    #[error("Accounts don't match the proposal")]
+    ProposalAccountsMismatch,
+
+    #[error("Instruction can't be executed by a multisig")]
+    InvalidMultisigInstruction,
+
+    #[error("Account layout is outdated, run MigrateAccount first")]
+    AccountNeedsMigration,
+
+    #[error("Account is already on the current layout")]
+    NothingToMigrate,
+
+    #[error("Whitelist was revoked for this client")]
+    WhitelistRevoked,
 }
 
 impl PrintProgramError for PresaleError {
@@ -7,6 +7,20 @@ use solana_program::{
 pub use crate::processor::{
     buy::BuyArgs,
     distribute_token::DistributeTokenArgs,
+    init_presale::InitPresaleArgs,
+    withdraw_funds::WithdrawFundsArgs,
+    add_to_whitelist::AddToWhitelistArgs,
+    add_round::AddRoundArgs,
+    register_client::RegisterClientArgs,
+    set_merkle_root::SetMerkleRootArgs,
+    add_payment_mint::AddPaymentMintArgs,
+    set_buy_limits::SetBuyLimitsArgs,
+    create_multisig::CreateMultisigArgs,
+    execute_multisig::ExecuteMultisigArgs,
+    propose_multisig::ProposeMultisigArgs,
+    set_role::SetRoleArgs,
+    migrate_account::MigrateAccountArgs,
+    resize_account::ResizeAccountArgs,
 };
 
 #[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
@@ -15,10 +29,29 @@ pub enum PresaleInstruction {
     StartPresale,
     StopPresale,
     StopWhiteList,
-    SetAuthority,
-    // WithdrawFunds(WithdrawFundsArgs),
-    // WithdrawUnsoldTokens(WithdrawUnsoldTokens),
+    ProposeAuthority,
     Buy(BuyArgs),
     DistributeToken(DistributeTokenArgs),
-    AddToWhitelist,
+    AddToWhitelist(AddToWhitelistArgs),
+    WithdrawFunds(WithdrawFundsArgs),
+    WithdrawUnsoldTokens,
+    Claim,
+    AddRound(AddRoundArgs),
+    Finalize,
+    Refund,
+    RegisterClient(RegisterClientArgs),
+    SetMerkleRoot(SetMerkleRootArgs),
+    RemoveFromWhitelist,
+    AddPaymentMint(AddPaymentMintArgs),
+    SetBuyLimits(SetBuyLimitsArgs),
+    AcceptAuthority,
+    CancelProposal,
+    CreateMultisig(CreateMultisigArgs),
+    ExecuteMultisig(ExecuteMultisigArgs),
+    ProposeMultisig(ProposeMultisigArgs),
+    ApproveMultisig,
+    ExecuteMultisigProposal,
+    SetRole(SetRoleArgs),
+    MigrateAccount(MigrateAccountArgs),
+    ResizeAccount(ResizeAccountArgs),
 }
@@ -5,7 +5,11 @@ mod utils;
 pub mod entrypoint;
 pub mod errors;
 pub mod instruction;
+pub mod merkle;
+pub mod oracle;
 pub mod processor;
 
 pub const PRESALE: &str = "presale";
+pub const VAULT: &str = "vault";
+pub const MULTISIG: &str = "multisig";
 
@@ -0,0 +1,191 @@
+use solana_program::{keccak::hashv, pubkey::Pubkey};
+#[cfg(not(target_os = "solana"))]
+use std::str::FromStr;
+
+/// Leaf of the whitelist tree: the member, the rounds it may buy in and its personal cap (0 for none)
+pub fn hash_leaf(owner : &Pubkey, rounds : u8, allocation_cap : u64) -> [u8; 32] {
+    hashv(&[owner.as_ref(), &[rounds], &allocation_cap.to_le_bytes()]).to_bytes()
+}
+
+/// Pairs are hashed in sorted order, so proofs don't need to carry left/right flags
+pub fn hash_pair(a : &[u8; 32], b : &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[a, b]).to_bytes()
+    } else {
+        hashv(&[b, a]).to_bytes()
+    }
+}
+
+pub fn verify(proof : &[[u8; 32]], root : &[u8; 32], leaf : [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == *root
+}
+
+// everything below only builds trees off-chain and stays out of the program binary
+
+///Structure with one whitelist entry
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct WhitelistEntry{
+    pub owner : Pubkey,
+    pub rounds : u8,
+    pub allocation_cap : u64,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl WhitelistEntry{
+    pub fn leaf(&self) -> [u8; 32] {
+        hash_leaf(&self.owner, self.rounds, self.allocation_cap)
+    }
+}
+
+/// Parses `address,rounds,allocation_cap` lines; a header line and blank lines are skipped
+#[cfg(not(target_os = "solana"))]
+pub fn parse_csv(csv : &str) -> Result<Vec<WhitelistEntry>, String> {
+    let mut entries = Vec::new();
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line.starts_with("address")) {
+            continue;
+        }
+        let fields : Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if fields.len() != 3 {
+            return Err(format!("line {}: expected address,rounds,allocation_cap", i + 1));
+        }
+        entries.push(WhitelistEntry{
+            owner : Pubkey::from_str(fields[0]).map_err(|e| format!("line {}: {}", i + 1, e))?,
+            rounds : fields[1].parse().map_err(|e| format!("line {}: {}", i + 1, e))?,
+            allocation_cap : fields[2].parse().map_err(|e| format!("line {}: {}", i + 1, e))?,
+        });
+    }
+    Ok(entries)
+}
+
+///Structure for building the whitelist tree off-chain
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree{
+    layers : Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree{
+    pub fn new(leaves : Vec<[u8; 32]>) -> MerkleTree {
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers.last().unwrap()
+                .chunks(2)
+                .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+                .collect();
+            layers.push(next);
+        }
+        MerkleTree{ layers }
+    }
+
+    pub fn from_entries(entries : &[WhitelistEntry]) -> MerkleTree {
+        MerkleTree::new(entries.iter().map(|e| e.leaf()).collect())
+    }
+
+    pub fn from_csv(csv : &str) -> Result<(MerkleTree, Vec<WhitelistEntry>), String> {
+        let entries = parse_csv(csv)?;
+        Ok((MerkleTree::from_entries(&entries), entries))
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap().first().copied().unwrap_or_default()
+    }
+
+    /// Proof for the leaf at `index`, None when out of range
+    pub fn proof(&self, mut index : usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.layers[0].len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < layer.len() {
+                proof.push(layer[sibling]);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(n : usize) -> Vec<WhitelistEntry> {
+        (0..n).map(|i| WhitelistEntry{
+            owner : Pubkey::new_unique(),
+            rounds : 1 << (i % 4),
+            allocation_cap : 1_000 * i as u64,
+        }).collect()
+    }
+
+    fn assert_round_trip(n : usize) {
+        let entries = entries(n);
+        let tree = MerkleTree::from_entries(&entries);
+        for (i, entry) in entries.iter().enumerate() {
+            let proof = tree.proof(i).unwrap();
+            assert!(verify(&proof, &tree.root(), entry.leaf()), "leaf {} of {}", i, n);
+        }
+        assert_eq!(tree.proof(n), None);
+    }
+
+    #[test]
+    fn single_leaf_tree() {
+        let entries = entries(1);
+        let tree = MerkleTree::from_entries(&entries);
+        assert_eq!(tree.root(), entries[0].leaf());
+        assert_eq!(tree.proof(0), Some(vec![]));
+        assert_round_trip(1);
+    }
+
+    #[test]
+    fn two_leaf_tree() {
+        let entries = entries(2);
+        let tree = MerkleTree::from_entries(&entries);
+        assert_eq!(tree.root(), hash_pair(&entries[0].leaf(), &entries[1].leaf()));
+        assert_round_trip(2);
+    }
+
+    #[test]
+    fn odd_sized_trees() {
+        for n in &[3, 5, 7, 9] {
+            assert_round_trip(*n);
+        }
+    }
+
+    #[test]
+    fn tampered_leaf_is_rejected() {
+        let entries = entries(5);
+        let tree = MerkleTree::from_entries(&entries);
+        let entry = &entries[3];
+        let proof = tree.proof(3).unwrap();
+
+        assert!(!verify(&proof, &tree.root(), hash_leaf(&entry.owner, entry.rounds, entry.allocation_cap + 1)));
+        assert!(!verify(&proof, &tree.root(), hash_leaf(&entry.owner, 0b1111, entry.allocation_cap)));
+        assert!(!verify(&proof, &tree.root(), hash_leaf(&Pubkey::new_unique(), entry.rounds, entry.allocation_cap)));
+        assert!(!verify(&tree.proof(2).unwrap(), &tree.root(), entry.leaf()));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let entries = entries(3);
+        let mut csv = String::from("address,rounds,allocation_cap\n");
+        for entry in &entries {
+            csv.push_str(&format!("{}, {}, {}\n\n", entry.owner, entry.rounds, entry.allocation_cap));
+        }
+
+        let (tree, parsed) = MerkleTree::from_csv(&csv).unwrap();
+        assert_eq!(parsed, entries);
+        assert_eq!(tree.root(), MerkleTree::from_entries(&entries).root());
+    }
+
+    #[test]
+    fn csv_errors_name_the_line() {
+        assert!(parse_csv("address,rounds,allocation_cap\nnot-a-key,1,0").unwrap_err().starts_with("line 2"));
+        assert!(parse_csv(&format!("{},1", Pubkey::new_unique())).unwrap_err().starts_with("line 1"));
+        assert!(parse_csv(&format!("{},256,0", Pubkey::new_unique())).unwrap_err().starts_with("line 1"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,73 @@
+use {
+    crate::errors::PresaleError,
+    arrayref::array_ref,
+    solana_program::{account_info::AccountInfo, program_error::ProgramError},
+};
+
+/// Price accounts follow the Pyth v2 layout, only the fields read here are listed
+pub const PRICE_MAGIC : u32 = 0xa1b2c3d4;
+pub const PRICE_ACCOUNT_TYPE : u32 = 3;
+pub const PRICE_STATUS_TRADING : u32 = 1;
+const MAGIC_OFFSET : usize = 0;
+const ACCOUNT_TYPE_OFFSET : usize = 8;
+const EXPO_OFFSET : usize = 20;
+const AGG_PRICE_OFFSET : usize = 208;
+const AGG_CONF_OFFSET : usize = 216;
+const AGG_STATUS_OFFSET : usize = 224;
+const AGG_PUB_SLOT_OFFSET : usize = 232;
+pub const PRICE_ACCOUNT_MIN_SIZE : usize = AGG_PUB_SLOT_OFFSET + 8;
+
+///Structure with the aggregate price of a feed
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PriceFeed{
+    pub price : i64,
+    pub conf : u64,
+    pub expo : i32,
+    pub status : u32,
+    pub pub_slot : u64,
+}
+
+impl PriceFeed{
+    pub fn from_account_info(a : &AccountInfo) -> Result<PriceFeed,ProgramError>{
+        let data = a.data.borrow();
+        if data.len() < PRICE_ACCOUNT_MIN_SIZE
+            || u32::from_le_bytes(*array_ref![data, MAGIC_OFFSET, 4]) != PRICE_MAGIC
+            || u32::from_le_bytes(*array_ref![data, ACCOUNT_TYPE_OFFSET, 4]) != PRICE_ACCOUNT_TYPE {
+            return Err(PresaleError::InvalidOracle.into());
+        }
+        Ok(PriceFeed{
+            price : i64::from_le_bytes(*array_ref![data, AGG_PRICE_OFFSET, 8]),
+            conf : u64::from_le_bytes(*array_ref![data, AGG_CONF_OFFSET, 8]),
+            expo : i32::from_le_bytes(*array_ref![data, EXPO_OFFSET, 4]),
+            status : u32::from_le_bytes(*array_ref![data, AGG_STATUS_OFFSET, 4]),
+            pub_slot : u64::from_le_bytes(*array_ref![data, AGG_PUB_SLOT_OFFSET, 8]),
+        })
+    }
+
+    /// Price as numerator / denominator, once it is trading, fresh and tight enough
+    pub fn checked_rate(
+        &self,
+        current_slot : u64,
+        max_staleness_slots : u64,
+        max_confidence_bps : u64,
+        ) -> Result<(u64, u64),ProgramError> {
+        if self.status != PRICE_STATUS_TRADING || self.price <= 0 {
+            return Err(PresaleError::InvalidOracle.into());
+        }
+        if current_slot.saturating_sub(self.pub_slot) > max_staleness_slots {
+            return Err(PresaleError::StalePrice.into());
+        }
+
+        let price = self.price as u64;
+        if (self.conf as u128) * 10_000 > (price as u128) * (max_confidence_bps as u128) {
+            return Err(PresaleError::PriceConfidenceTooWide.into());
+        }
+
+        let scale = 10u64.checked_pow(self.expo.unsigned_abs()).ok_or(PresaleError::MathOverflow)?;
+        if self.expo < 0 {
+            Ok((price, scale))
+        } else {
+            Ok((price.checked_mul(scale).ok_or(PresaleError::MathOverflow)?, 1))
+        }
+    }
+}
\ No newline at end of file
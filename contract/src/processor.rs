@@ -1,9 +1,13 @@
-use crate::errors::PresaleError;
+use crate::{
+    errors::PresaleError,
+    oracle::PriceFeed,
+    utils::{convert_amount,Rounding},
+};
 use arrayref::array_ref;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    account_info::AccountInfo, borsh::try_from_slice_unchecked, clock::UnixTimestamp,
-    entrypoint::ProgramResult, hash::Hash, msg, program_error::ProgramError, pubkey::Pubkey,
+    account_info::AccountInfo, borsh::try_from_slice_unchecked, clock::{Clock, UnixTimestamp},
+    entrypoint::ProgramResult, hash::Hash, msg, program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
 };
 use std::{cell::Ref, cmp, mem};
 
@@ -11,19 +15,61 @@ pub mod add_to_whitelist;
 pub mod start_presale;
 pub mod stop_presale;
 pub mod stop_whitelist;
-pub mod set_authority;
+pub mod propose_authority;
 pub mod buy;
 pub mod distribute_token;
 pub mod init_presale;
+pub mod withdraw_funds;
+pub mod withdraw_unsold_tokens;
+pub mod claim;
+pub mod add_round;
+pub mod finalize;
+pub mod refund;
+pub mod register_client;
+pub mod set_merkle_root;
+pub mod remove_from_whitelist;
+pub mod add_payment_mint;
+pub mod set_buy_limits;
+pub mod accept_authority;
+pub mod cancel_proposal;
+pub mod create_multisig;
+pub mod execute_multisig;
+pub mod propose_multisig;
+pub mod approve_multisig;
+pub mod execute_multisig_proposal;
+pub mod set_role;
+pub mod migrate_account;
+pub mod resize_account;
 
 pub use add_to_whitelist::*;
 pub use start_presale::*;
 pub use stop_presale::*;
 pub use stop_whitelist::*;
-pub use set_authority::*;
+pub use propose_authority::*;
 pub use buy::*;
 pub use distribute_token::*;
 pub use init_presale::*;
+pub use withdraw_funds::*;
+pub use withdraw_unsold_tokens::*;
+pub use claim::*;
+pub use add_round::*;
+pub use finalize::*;
+pub use refund::*;
+pub use register_client::*;
+pub use set_merkle_root::*;
+pub use remove_from_whitelist::*;
+pub use add_payment_mint::*;
+pub use set_buy_limits::*;
+pub use accept_authority::*;
+pub use cancel_proposal::*;
+pub use create_multisig::*;
+pub use execute_multisig::*;
+pub use propose_multisig::*;
+pub use approve_multisig::*;
+pub use execute_multisig_proposal::*;
+pub use set_role::*;
+pub use migrate_account::*;
+pub use resize_account::*;
 
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -32,63 +78,696 @@ pub fn process_instruction(
 ) -> ProgramResult {
     use crate::instruction::PresaleInstruction;
     match PresaleInstruction::try_from_slice(input)? {
-        PresaleInstruction::AddToWhitelist => add_to_whitelist(program_id, accounts),
+        PresaleInstruction::AddToWhitelist(args) => add_to_whitelist(program_id, accounts, args),
         PresaleInstruction::StartPresale => start_presale(program_id, accounts),
         PresaleInstruction::StopPresale => stop_presale(program_id, accounts),
         PresaleInstruction::StopWhiteList => stop_whitelist(program_id,accounts),
-        PresaleInstruction::SetAuthority => set_authority(program_id,accounts),
+        PresaleInstruction::ProposeAuthority => propose_authority(program_id,accounts),
         PresaleInstruction::Buy(args) => buy(program_id,accounts,args),
         PresaleInstruction::DistributeToken(args) => distribute_token(program_id,accounts,args),
         PresaleInstruction::InitPresale(args) => init_presale(program_id,accounts,args),
+        PresaleInstruction::WithdrawFunds(args) => withdraw_funds(program_id,accounts,args),
+        PresaleInstruction::WithdrawUnsoldTokens => withdraw_unsold_tokens(program_id,accounts),
+        PresaleInstruction::Claim => claim(program_id,accounts),
+        PresaleInstruction::AddRound(args) => add_round(program_id,accounts,args),
+        PresaleInstruction::Finalize => finalize(program_id,accounts),
+        PresaleInstruction::Refund => refund(program_id,accounts),
+        PresaleInstruction::RegisterClient(args) => register_client(program_id,accounts,args),
+        PresaleInstruction::SetMerkleRoot(args) => set_merkle_root(program_id,accounts,args),
+        PresaleInstruction::RemoveFromWhitelist => remove_from_whitelist(program_id,accounts),
+        PresaleInstruction::AddPaymentMint(args) => add_payment_mint(program_id,accounts,args),
+        PresaleInstruction::SetBuyLimits(args) => set_buy_limits(program_id,accounts,args),
+        PresaleInstruction::AcceptAuthority => accept_authority(program_id,accounts),
+        PresaleInstruction::CancelProposal => cancel_proposal(program_id,accounts),
+        PresaleInstruction::CreateMultisig(args) => create_multisig(program_id,accounts,args),
+        PresaleInstruction::ExecuteMultisig(args) => execute_multisig(program_id,accounts,args),
+        PresaleInstruction::ProposeMultisig(args) => propose_multisig(program_id,accounts,args),
+        PresaleInstruction::ApproveMultisig => approve_multisig(program_id,accounts),
+        PresaleInstruction::ExecuteMultisigProposal => execute_multisig_proposal(program_id,accounts),
+        PresaleInstruction::SetRole(args) => set_role(program_id,accounts,args),
+        PresaleInstruction::MigrateAccount(args) => migrate_account(program_id,accounts,args),
+        PresaleInstruction::ResizeAccount(args) => resize_account(program_id,accounts,args),
     }
 }
 
+/// Leading bytes of every account owned by the program
+#[repr(u8)]
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub enum AccountType{
+    Uninitialized,
+    Presale,
+    Client,
+    Multisig,
+    MultisigProposal,
+}
+
+pub const ACCOUNT_HEADER_SIZE : usize = 1 + 1;
+pub const PRESALE_DATA_VERSION : u8 = 2;
+pub const CLIENT_DATA_VERSION : u8 = 2;
+pub const MULTISIG_DATA_VERSION : u8 = 1;
+pub const MULTISIG_PROPOSAL_VERSION : u8 = 1;
+
+/// Checks the discriminator and that the layout is the current `version`
+pub fn assert_account_header(a : &AccountInfo, account_type : AccountType, version : u8) -> ProgramResult {
+    let data = a.data.borrow();
+    if data.len() < ACCOUNT_HEADER_SIZE || data[0] != account_type as u8 {
+        return Err(PresaleError::DataTypeMismatch.into());
+    }
+    if data[1] != version {
+        return Err(PresaleError::AccountNeedsMigration.into());
+    }
+    Ok(())
+}
+
 ///Structure with client data
-pub const CLIENT_DATA_SIZE : usize = 32 + 32 + 8 + 1 + 1;
+pub const CLIENT_DATA_SIZE : usize = ACCOUNT_HEADER_SIZE + 32 + 32 + 8 + 8 * MAX_ROUNDS + 1 + 8 + 8 + 8 * MAX_PAYMENT_MINTS + 1 + 8 + 8 + 1 + CLIENT_DATA_RESERVED;
+/// new fields take their space from the reserved bytes, so existing accounts keep their size
+pub const CLIENT_DATA_RESERVED : usize = 63;
 #[repr(C)]
 #[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
 pub struct ClientData{
+    pub account_type : AccountType,
+    pub version : u8,
     pub owner : Pubkey,
     pub presale : Pubkey,
     pub amount : u64,
-    pub is_whitelisted : bool,
-    pub already_paid : bool,
+    pub round_amounts : [u64; MAX_ROUNDS],
+    /// bit i set when whitelisted for round i
+    pub whitelisted_rounds : u8,
+    pub claimed : u64,
+    /// personal limit per round, 0 means only the round limits apply;
+    /// AddToWhitelist sets it, a whitelist proof can only lower it
+    pub allocation_cap : u64,
+    /// deposits per payment mint in that mint's own units, what a refund pays back
+    pub paid : [u64; MAX_PAYMENT_MINTS],
+    /// bit i set once the oversubscribed part of paid[i] was refunded
+    pub excess_refunded : u8,
+    pub buy_count : u64,
+    pub last_buy_slot : u64,
+    /// set by RemoveFromWhitelist, a whitelist proof no longer registers this client
+    pub whitelist_revoked : bool,
+    pub reserved : [u8; CLIENT_DATA_RESERVED],
 }
 
 impl ClientData{
     pub fn from_account_info(a : &AccountInfo) -> Result<ClientData,ProgramError>{
-        if a.data_len() != CLIENT_DATA_SIZE {
+        assert_account_header(a, AccountType::Client, CLIENT_DATA_VERSION)?;
+        // accounts may have been grown by ResizeAccount
+        if a.data_len() < CLIENT_DATA_SIZE {
             return Err(PresaleError::DataTypeMismatch.into());
         }
         let client : ClientData = try_from_slice_unchecked(&a.data.borrow_mut())?;
         Ok(client)
     }
+
+    pub fn is_whitelisted(&self, round : usize) -> bool {
+        self.whitelisted_rounds & (1 << round) != 0
+    }
+
+    pub fn is_excess_refunded(&self, index : usize) -> bool {
+        self.excess_refunded & (1 << index) != 0
+    }
+}
+
+///Structure for a discrete vesting unlock
+pub const VESTING_TRANCHE_SIZE : usize = 8 + 8;
+#[repr(C)]
+#[derive(Clone, Copy, Default, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct VestingTranche{
+    pub unlock_ts : UnixTimestamp,
+    pub percentage : u64,
+}
+
+///Structure for the vesting schedule of purchased tokens
+pub const MAX_VESTING_TRANCHES : usize = 4;
+pub const VESTING_SIZE : usize = 8 + 8 + 8 + VESTING_TRANCHE_SIZE * MAX_VESTING_TRANCHES;
+#[repr(C)]
+#[derive(Clone, Default, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct Vesting{
+    pub tge_percentage : u64,
+    pub cliff_ts : UnixTimestamp,
+    pub duration : i64,
+    pub tranches : [VestingTranche; MAX_VESTING_TRANCHES],
+}
+
+impl Vesting{
+    pub fn is_empty(&self) -> bool {
+        *self == Vesting::default()
+    }
+
+    pub fn has_tranches(&self) -> bool {
+        self.tranches.iter().any(|t| t.percentage != 0)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        if self.tge_percentage > 100 || self.duration < 0 {
+            return false;
+        }
+        if self.has_tranches() {
+            // tranches replace the linear part, so together with the TGE unlock they must cover everything
+            let total = self.tranches.iter().fold(self.tge_percentage, |acc, t| acc.saturating_add(t.percentage));
+            return total == 100 && self.duration == 0;
+        }
+        true
+    }
+
+    /// Amount of `total` unlocked by the schedule at `now`
+    pub fn vested_amount(&self, total : u64, now : UnixTimestamp) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        let total = total as u128;
+        let tge = total * self.tge_percentage as u128 / 100;
+
+        let vested = if self.has_tranches() {
+            self.tranches.iter()
+                .filter(|t| t.percentage != 0 && t.unlock_ts <= now)
+                .fold(tge, |acc, t| acc + total * t.percentage as u128 / 100)
+        } else if now < self.cliff_ts {
+            tge
+        } else if self.duration == 0 {
+            total
+        } else {
+            let elapsed = cmp::min(now - self.cliff_ts, self.duration) as u128;
+            tge + (total - tge) * elapsed / self.duration as u128
+        };
+        cmp::min(vested, total) as u64
+    }
+}
+
+///Structure for the anti-bot limits on buys, 0 disables a limit
+pub const BUY_LIMITS_SIZE : usize = 8 + 8 + 8;
+#[repr(C)]
+#[derive(Clone, Copy, Default, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct BuyLimits{
+    pub max_buys_per_client : u64,
+    pub min_slots_between_buys : u64,
+    /// buys accepted from all clients together within one slot
+    pub max_buys_per_slot : u64,
+}
+
+///Structure for a sale round
+pub const MAX_ROUNDS : usize = 4;
+pub const ROUND_SIZE : usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+#[repr(C)]
+#[derive(Clone, Copy, Default, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct Round{
+    /// the round with the latest start_ts that has passed is the active one
+    pub start_ts : UnixTimestamp,
+    /// whole tokens for sale per whole token being raised, as numerator / denominator
+    pub price_numerator : u64,
+    pub price_denominator : u64,
+    pub hardcap : u64,
+    pub min_allocation : u64,
+    pub max_allocation : u64,
+    pub total_raised : u64,
+    pub is_whitelist : bool,
+}
+
+impl Round{
+    pub fn is_valid(&self) -> bool {
+        self.price_numerator != 0
+            && self.price_denominator != 0
+            && self.max_allocation != 0
+            && self.min_allocation <= self.max_allocation
+            && self.hardcap != 0
+    }
+}
+
+///Structure for the price feed of a payment mint
+pub const ORACLE_CONFIG_SIZE : usize = 32 + 32 + 8 + 8;
+#[repr(C)]
+#[derive(Clone, Copy, Default, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct OracleConfig{
+    /// price feed account, default when the mint uses its fixed rate
+    pub feed : Pubkey,
+    /// program that must own the feed account
+    pub program : Pubkey,
+    pub max_staleness_slots : u64,
+    pub max_confidence_bps : u64,
+}
+
+impl OracleConfig{
+    pub fn is_enabled(&self) -> bool {
+        self.feed != Pubkey::default()
+    }
+}
+
+///Structure for an accepted payment mint
+pub const MAX_PAYMENT_MINTS : usize = 4;
+pub const PAYMENT_MINT_SIZE : usize = 32 + 8 + 8 + 1 + 8 + ORACLE_CONFIG_SIZE + 8;
+#[repr(C)]
+#[derive(Clone, Copy, Default, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct PaymentMint{
+    pub mint : Pubkey,
+    /// whole tokens being raised per whole token of this mint, as numerator / denominator
+    pub rate_numerator : u64,
+    pub rate_denominator : u64,
+    pub decimals : u8,
+    pub total_raised : u64,
+    /// when enabled the feed price replaces the fixed rate
+    pub oracle : OracleConfig,
+    /// oversubscribed deposits already paid back
+    pub total_refunded : u64,
+}
+
+impl PaymentMint{
+    /// Current rate of this mint, `feed_account` is only read for oracle priced mints
+    pub fn rate(&self, feed_account : Option<&AccountInfo>) -> Result<(u64, u64),ProgramError> {
+        if !self.oracle.is_enabled() {
+            return Ok((self.rate_numerator, self.rate_denominator));
+        }
+        let feed_account = feed_account.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if *feed_account.key != self.oracle.feed || *feed_account.owner != self.oracle.program {
+            return Err(PresaleError::InvalidOracle.into());
+        }
+        PriceFeed::from_account_info(feed_account)?.checked_rate(
+            Clock::get()?.slot,
+            self.oracle.max_staleness_slots,
+            self.oracle.max_confidence_bps,
+        )
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub enum Role{
+    WhitelistManager,
+    Pauser,
+    Treasurer,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub enum PresaleStatus{
+    Pending,
+    Succeeded,
+    Failed,
 }
 
 ///Structure for Presale Data
-pub const PRESALE_DATA_SIZE : usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+pub const PRESALE_DATA_SIZE : usize = ACCOUNT_HEADER_SIZE + 32 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 1 + 1 + VESTING_SIZE + 8 + 8 + 8 + 1 + ROUND_SIZE * MAX_ROUNDS + 32 + 1 + PAYMENT_MINT_SIZE * MAX_PAYMENT_MINTS + 1 + BUY_LIMITS_SIZE + 8 + 8 + 32 + 32 + 32 + 32 + PRESALE_DATA_RESERVED;
+pub const PRESALE_DATA_RESERVED : usize = 128;
 #[repr(C)]
 #[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
 pub struct PresaleData{
+    pub account_type : AccountType,
+    pub version : u8,
     pub authority : Pubkey,
     pub token_for_sale : Pubkey,
     pub token_being_raised : Pubkey,
-    pub min_allocation : u64,
-    pub max_allocation : u64,
     pub hardcap : u64,
-    pub token_per_usd : f64,
+    pub softcap : u64,
+    pub sale_decimals : u8,
+    pub raise_decimals : u8,
     pub total_raised : u64,
     pub total_percentage_distributed : u64,
+    pub total_claimed : u64,
     pub is_active : bool,
     pub is_whitelist : bool,
+    pub status : PresaleStatus,
+    pub vesting : Vesting,
+    /// sale window, 0 means not set
+    pub start_ts : UnixTimestamp,
+    pub end_ts : UnixTimestamp,
+    pub whitelist_end_ts : UnixTimestamp,
+    pub round_count : u8,
+    pub rounds : [Round; MAX_ROUNDS],
+    /// root of the whitelist tree, all zeros when not used
+    pub merkle_root : [u8; 32],
+    /// slot 0 is always the token being raised at 1:1, amounts are accounted in its units
+    pub payment_mint_count : u8,
+    pub payment_mints : [PaymentMint; MAX_PAYMENT_MINTS],
+    /// deposits above the hardcap are accepted and everyone is filled pro rata
+    pub is_fair_launch : bool,
+    pub buy_limits : BuyLimits,
+    /// slot of the latest buy and the number of buys seen in it
+    pub last_buy_slot : u64,
+    pub slot_buy_count : u64,
+    /// proposed by the authority, default when no handover is in progress
+    pub pending_authority : Pubkey,
+    /// role keys assigned by the authority, default while the authority holds the role itself
+    pub whitelist_manager : Pubkey,
+    pub pauser : Pubkey,
+    pub treasurer : Pubkey,
+    pub reserved : [u8; PRESALE_DATA_RESERVED],
 }
 
 impl PresaleData{
     pub fn from_account_info(a : &AccountInfo) -> Result<PresaleData,ProgramError>{
-        if a.data_len() != PRESALE_DATA_SIZE {
+        assert_account_header(a, AccountType::Presale, PRESALE_DATA_VERSION)?;
+        if a.data_len() < PRESALE_DATA_SIZE {
             return Err(PresaleError::DataTypeMismatch.into());
         }
         let presale : PresaleData = try_from_slice_unchecked(&a.data.borrow_mut())?;
         Ok(presale)
     }
+
+    /// Tokens for sale bought with `amount` of the token being raised in `round`
+    pub fn sale_amount(&self, round : usize, amount : u64, rounding : Rounding) -> Result<u64,ProgramError> {
+        convert_amount(
+            amount,
+            self.rounds[round].price_numerator,
+            self.rounds[round].price_denominator,
+            self.raise_decimals,
+            self.sale_decimals,
+            rounding,
+        )
+    }
+
+    /// Tokens for sale bought with `amounts` raised per round
+    pub fn total_sale_amount(&self, amounts : &[u64; MAX_ROUNDS], rounding : Rounding) -> Result<u64,ProgramError> {
+        let mut total : u64 = 0;
+        for round in 0..self.round_count as usize {
+            total = total
+                .checked_add(self.sale_amount(round, amounts[round], rounding)?)
+                .ok_or(PresaleError::MathOverflow)?;
+        }
+        Ok(total)
+    }
+
+    pub fn payment_mint_index(&self, mint : &Pubkey) -> Option<usize> {
+        (0..self.payment_mint_count as usize).find(|&i| self.payment_mints[i].mint == *mint)
+    }
+
+    /// `amount` of the payment mint at `index` expressed in units of the token being raised
+    pub fn normalize(&self, index : usize, amount : u64, rate : (u64, u64), rounding : Rounding) -> Result<u64,ProgramError> {
+        convert_amount(
+            amount,
+            rate.0,
+            rate.1,
+            self.payment_mints[index].decimals,
+            self.raise_decimals,
+            rounding,
+        )
+    }
+
+    /// Inverse of `normalize`, the amount of the payment mint at `index` worth `amount` of the token being raised
+    pub fn denormalize(&self, index : usize, amount : u64, rate : (u64, u64), rounding : Rounding) -> Result<u64,ProgramError> {
+        convert_amount(
+            amount,
+            rate.1,
+            rate.0,
+            self.raise_decimals,
+            self.payment_mints[index].decimals,
+            rounding,
+        )
+    }
+
+    pub fn role_key(&self, role : Role) -> Pubkey {
+        let key = match role {
+            Role::WhitelistManager => self.whitelist_manager,
+            Role::Pauser => self.pauser,
+            Role::Treasurer => self.treasurer,
+        };
+        if key == Pubkey::default() { self.authority } else { key }
+    }
+
+    pub fn is_oversubscribed(&self) -> bool {
+        self.is_fair_launch && self.total_raised > self.hardcap
+    }
+
+    /// Part of a deposit that is filled, all of it unless the fair launch is oversubscribed
+    pub fn allocated(&self, amount : u64, rounding : Rounding) -> Result<u64,ProgramError> {
+        if !self.is_oversubscribed() {
+            return Ok(amount);
+        }
+        convert_amount(amount, self.hardcap, self.total_raised, 0, 0, rounding)
+    }
+
+    pub fn allocated_per_round(&self, amounts : &[u64; MAX_ROUNDS], rounding : Rounding) -> Result<[u64; MAX_ROUNDS],ProgramError> {
+        let mut allocated = [0u64; MAX_ROUNDS];
+        for (allocated, amount) in allocated.iter_mut().zip(amounts.iter()) {
+            *allocated = self.allocated(*amount, rounding)?;
+        }
+        Ok(allocated)
+    }
+
+    /// Part of a deposit that is not filled and goes back to the buyer
+    pub fn excess(&self, amount : u64, rounding : Rounding) -> Result<u64,ProgramError> {
+        if !self.is_oversubscribed() {
+            return Ok(0);
+        }
+        convert_amount(amount, self.total_raised - self.hardcap, self.total_raised, 0, 0, rounding)
+    }
+
+    /// Deposits of the payment mint at `index` still owed back to oversubscribed buyers
+    pub fn reserved_for_refunds(&self, index : usize) -> Result<u64,ProgramError> {
+        let payment_mint = &self.payment_mints[index];
+        Ok(self.excess(payment_mint.total_raised, Rounding::Up)?.saturating_sub(payment_mint.total_refunded))
+    }
+
+    pub fn raised_per_round(&self) -> [u64; MAX_ROUNDS] {
+        let mut raised = [0u64; MAX_ROUNDS];
+        for (amount, round) in raised.iter_mut().zip(self.rounds.iter()) {
+            *amount = round.total_raised;
+        }
+        raised
+    }
+
+    pub fn active_round(&self, now : UnixTimestamp) -> Option<usize> {
+        (0..self.round_count as usize).rev().find(|&i| now >= self.rounds[i].start_ts)
+    }
+
+    /// Native SOL is raised as lamports into a system owned vault instead of a token account
+    pub fn is_native(&self) -> bool {
+        self.token_being_raised == spl_token::native_mint::id()
+    }
+
+    pub fn is_open(&self, now : UnixTimestamp) -> bool {
+        self.is_active
+            && (self.start_ts == 0 || now >= self.start_ts)
+            && !self.is_ended(now)
+    }
+
+    pub fn is_ended(&self, now : UnixTimestamp) -> bool {
+        self.end_ts != 0 && now >= self.end_ts
+    }
+
+    /// Stopped by the authority or past the end of the window
+    pub fn is_closed(&self, now : UnixTimestamp) -> bool {
+        !self.is_active || self.is_ended(now)
+    }
+
+    /// Funds and tokens only move to their final owners once the sale succeeded
+    pub fn assert_succeeded(&self) -> ProgramResult {
+        match self.status {
+            PresaleStatus::Pending => Err(PresaleError::NotFinalized.into()),
+            PresaleStatus::Failed => Err(PresaleError::SaleFailed.into()),
+            PresaleStatus::Succeeded => Ok(()),
+        }
+    }
+
+    pub fn is_whitelist_required(&self, now : UnixTimestamp) -> bool {
+        self.is_whitelist && (self.whitelist_end_ts == 0 || now < self.whitelist_end_ts)
+    }
+}
+
+///Structure for an M-of-N account that can act as a presale authority
+pub const MAX_MULTISIG_SIGNERS : usize = 10;
+pub const MULTISIG_DATA_SIZE : usize = ACCOUNT_HEADER_SIZE + 1 + 1 + 32 * MAX_MULTISIG_SIGNERS + 8;
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct MultisigData{
+    pub account_type : AccountType,
+    pub version : u8,
+    pub threshold : u8,
+    pub signer_count : u8,
+    pub signers : [Pubkey; MAX_MULTISIG_SIGNERS],
+    /// index of the next proposal, part of its address
+    pub proposal_count : u64,
+}
+
+impl MultisigData{
+    pub fn from_account_info(a : &AccountInfo) -> Result<MultisigData,ProgramError>{
+        assert_account_header(a, AccountType::Multisig, MULTISIG_DATA_VERSION)?;
+        if a.data_len() < MULTISIG_DATA_SIZE {
+            return Err(PresaleError::DataTypeMismatch.into());
+        }
+        let multisig : MultisigData = try_from_slice_unchecked(&a.data.borrow_mut())?;
+        Ok(multisig)
+    }
+
+    pub fn signer_index(&self, key : &Pubkey) -> Option<usize> {
+        (0..self.signer_count as usize).find(|&i| self.signers[i] == *key)
+    }
+
+    /// Bitmask of the members that signed among `accounts`
+    pub fn signed(&self, accounts : &[AccountInfo]) -> u16 {
+        accounts.iter()
+            .filter(|a| a.is_signer)
+            .filter_map(|a| self.signer_index(a.key))
+            .fold(0, |mask, i| mask | (1 << i))
+    }
+
+    pub fn is_approved(&self, approvals : u16) -> bool {
+        approvals.count_ones() >= self.threshold as u32
+    }
+}
+
+///Structure for an instruction waiting on multisig approvals
+pub const MULTISIG_PROPOSAL_BASE_SIZE : usize = ACCOUNT_HEADER_SIZE + 32 + 8 + 2 + 1 + 4 + 4;
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct MultisigProposal{
+    pub account_type : AccountType,
+    pub version : u8,
+    pub multisig : Pubkey,
+    pub index : u64,
+    /// bit i set once signer i approved
+    pub approvals : u16,
+    pub executed : bool,
+    /// keys the instruction has to be executed with, in order
+    pub accounts : Vec<Pubkey>,
+    pub data : Vec<u8>,
+}
+
+impl MultisigProposal{
+    pub fn from_account_info(a : &AccountInfo) -> Result<MultisigProposal,ProgramError>{
+        assert_account_header(a, AccountType::MultisigProposal, MULTISIG_PROPOSAL_VERSION)?;
+        if a.data_len() < MULTISIG_PROPOSAL_BASE_SIZE {
+            return Err(PresaleError::DataTypeMismatch.into());
+        }
+        let proposal : MultisigProposal = try_from_slice_unchecked(&a.data.borrow_mut())?;
+        Ok(proposal)
+    }
+
+    pub fn size(accounts : usize, data : usize) -> usize {
+        MULTISIG_PROPOSAL_BASE_SIZE + 32 * accounts + data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tranche(unlock_ts : UnixTimestamp, percentage : u64) -> VestingTranche {
+        VestingTranche{ unlock_ts, percentage }
+    }
+
+    fn linear(tge_percentage : u64, cliff_ts : UnixTimestamp, duration : i64) -> Vesting {
+        Vesting{ tge_percentage, cliff_ts, duration, tranches : Default::default() }
+    }
+
+    fn presale_with_rounds(raise_decimals : u8, sale_decimals : u8, prices : &[(u64, u64)]) -> PresaleData {
+        let mut presale : PresaleData = try_from_slice_unchecked(&vec![0; PRESALE_DATA_SIZE]).unwrap();
+        presale.raise_decimals = raise_decimals;
+        presale.sale_decimals = sale_decimals;
+        presale.round_count = prices.len() as u8;
+        for (round, (numerator, denominator)) in presale.rounds.iter_mut().zip(prices.iter()) {
+            round.price_numerator = *numerator;
+            round.price_denominator = *denominator;
+        }
+        presale
+    }
+
+    #[test]
+    fn sale_amount_uses_round_price_and_decimals() {
+        let presale = presale_with_rounds(6, 9, &[(10, 1), (5, 1)]);
+        assert_eq!(presale.sale_amount(0, 1_000_000, Rounding::Down), Ok(10_000_000_000));
+        assert_eq!(presale.sale_amount(1, 1_000_000, Rounding::Down), Ok(5_000_000_000));
+
+        let presale = presale_with_rounds(9, 6, &[(10, 1)]);
+        assert_eq!(presale.sale_amount(0, 1_000_000_001, Rounding::Down), Ok(10_000_000));
+        assert_eq!(presale.sale_amount(0, 1_000_000_001, Rounding::Up), Ok(10_000_001));
+    }
+
+    #[test]
+    fn sale_amount_rounding() {
+        let presale = presale_with_rounds(6, 6, &[(1, 3)]);
+        assert_eq!(presale.sale_amount(0, 10, Rounding::Down), Ok(3));
+        assert_eq!(presale.sale_amount(0, 10, Rounding::Up), Ok(4));
+    }
+
+    #[test]
+    fn total_sale_amount_sums_configured_rounds_only() {
+        let presale = presale_with_rounds(6, 9, &[(10, 1), (5, 1)]);
+        // rounds past round_count have no price and must be skipped
+        let amounts = [1_000_000, 2_000_000, 7, 0];
+        assert_eq!(presale.total_sale_amount(&amounts, Rounding::Down), Ok(20_000_000_000));
+    }
+
+    #[test]
+    fn total_sale_amount_overflow() {
+        let presale = presale_with_rounds(0, 0, &[(u64::MAX, 1), (u64::MAX, 1)]);
+        let overflow : ProgramError = PresaleError::MathOverflow.into();
+        assert_eq!(presale.total_sale_amount(&[1, 2, 0, 0], Rounding::Down), Err(overflow));
+    }
+
+    #[test]
+    fn empty_vesting_unlocks_nothing() {
+        assert_eq!(Vesting::default().vested_amount(1_000, 1_000_000), 0);
+    }
+
+    #[test]
+    fn tge_only_until_cliff() {
+        let vesting = linear(20, 1_000, 0);
+        assert_eq!(vesting.vested_amount(1_000, 0), 200);
+        assert_eq!(vesting.vested_amount(1_000, 999), 200);
+        assert_eq!(vesting.vested_amount(1_000, 1_000), 1_000);
+    }
+
+    #[test]
+    fn linear_after_cliff() {
+        let vesting = linear(10, 100, 1_000);
+        assert_eq!(vesting.vested_amount(1_000, 50), 100);
+        assert_eq!(vesting.vested_amount(1_000, 100), 100);
+        assert_eq!(vesting.vested_amount(1_000, 600), 550);
+        assert_eq!(vesting.vested_amount(1_000, 1_100), 1_000);
+        assert_eq!(vesting.vested_amount(1_000, 5_000), 1_000);
+    }
+
+    #[test]
+    fn tranches_unlock_in_steps() {
+        let mut vesting = linear(10, 0, 0);
+        vesting.tranches[0] = tranche(100, 30);
+        vesting.tranches[1] = tranche(200, 60);
+        assert!(vesting.is_valid());
+        assert_eq!(vesting.vested_amount(1_000, 50), 100);
+        assert_eq!(vesting.vested_amount(1_000, 150), 400);
+        assert_eq!(vesting.vested_amount(1_000, 250), 1_000);
+    }
+
+    #[test]
+    fn vested_amount_is_clamped_to_total() {
+        let mut vesting = linear(50, 0, 0);
+        vesting.tranches[0] = tranche(0, 100);
+        assert!(!vesting.is_valid());
+        assert_eq!(vesting.vested_amount(1_000, 10), 1_000);
+    }
+
+    #[test]
+    fn vesting_validation() {
+        assert!(linear(0, 0, 0).is_valid());
+        assert!(linear(100, 0, 0).is_valid());
+        assert!(!linear(101, 0, 0).is_valid());
+        assert!(!linear(10, 0, -1).is_valid());
+
+        let mut vesting = linear(10, 0, 0);
+        vesting.tranches[0] = tranche(100, 80);
+        assert!(!vesting.is_valid());
+        vesting.tranches[1] = tranche(200, 10);
+        assert!(vesting.is_valid());
+        vesting.duration = 100;
+        assert!(!vesting.is_valid());
+    }
+
+    #[test]
+    fn legacy_price_keeps_raw_rate() {
+        use crate::processor::migrate_account::legacy_price;
+
+        // 2.5 raw units sold per raw unit raised
+        let (numerator, denominator) = legacy_price(2.5, 6, 9).unwrap();
+        assert_eq!((numerator, denominator), (1, 400));
+        let presale = presale_with_rounds(6, 9, &[(numerator, denominator)]);
+        assert_eq!(presale.sale_amount(0, 1_000_000, Rounding::Down), Ok(2_500_000));
+
+        let (numerator, denominator) = legacy_price(1.0, 9, 6).unwrap();
+        assert_eq!((numerator, denominator), (1_000, 1));
+
+        assert!(legacy_price(0.0, 6, 6).is_err());
+        assert!(legacy_price(-1.0, 6, 6).is_err());
+        assert!(legacy_price(f64::NAN, 6, 6).is_err());
+        assert!(legacy_price(f64::INFINITY, 6, 6).is_err());
+    }
 }
\ No newline at end of file
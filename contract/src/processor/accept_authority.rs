@@ -20,30 +20,30 @@ use {
 	},
 };
 
-pub fn set_authority(
+pub fn accept_authority(
 	program_id : &Pubkey,
 	accounts : &[AccountInfo],
 	)->ProgramResult{
-	msg!("+ Processing SetAuthority");
+	msg!("+ Processing AcceptAuthority");
 	let account_iter = &mut accounts.iter();
-	let old_authority_account = next_account_info(account_iter)?;
 	let new_authority_account = next_account_info(account_iter)?;
 	let presale_account = next_account_info(account_iter)?;
 
 	assert_owned_by(presale_account, program_id)?;
-	assert_signer(old_authority_account)?;
+	assert_signer(new_authority_account)?;
 
 	let mut presale = PresaleData::from_account_info(presale_account)?;
 
-	if presale.authority != *old_authority_account.key {
-		return Err(PresaleError::InvalidAuthority.into());
+	if presale.pending_authority == Pubkey::default() {
+		return Err(PresaleError::NoPendingAuthority.into());
 	}
 
-	if new_authority_account.data_is_empty() || new_authority_account.lamports() == 0 {
+	if presale.pending_authority != *new_authority_account.key {
 		return Err(PresaleError::InvalidAuthority.into());
 	}
 
-	presale.authority = *new_authority_account.key;
+	presale.authority = presale.pending_authority;
+	presale.pending_authority = Pubkey::default();
 	presale.serialize(&mut *presale_account.data.borrow_mut())?;
 	Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,OracleConfig,PaymentMint,PresaleStatus,MAX_PAYMENT_MINTS},
+	utils::{
+		assert_owned_by,assert_signer,assert_authority,assert_derivation,create_or_allocate_account_raw,
+		spl_token_init_account,TokenInitAccountParams,
+	},
+	PRESALE,VAULT,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+	spl_token::state::{Account,Mint},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct AddPaymentMintArgs {
+	/// whole tokens being raised per whole token of the new mint, as numerator / denominator
+	pub rate_numerator : u64,
+	pub rate_denominator : u64,
+	/// price feed quoting the new mint in units of the token being raised, replaces the fixed rate
+	pub oracle : Option<OracleConfig>,
+}
+
+/// Accepts another mint as payment and creates its vault, `payer_account` funds the rent
+/// so that an authority without lamports, like a multisig, can add mints
+pub fn add_payment_mint(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : AddPaymentMintArgs,
+	)->ProgramResult{
+	msg!("+ Processing AddPaymentMint");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let payer_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let mint_account = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let vault_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+	assert_owned_by(mint_account,&spl_token::id())?;
+	assert_signer(payer_account)?;
+
+	if *token_program.key != spl_token::id() {
+		return Err(PresaleError::InvalidTokenProgram.into());
+	}
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, authority_account)?;
+
+	if presale.status != PresaleStatus::Pending {
+		return Err(PresaleError::AlreadyFinalized.into());
+	}
+
+	let index = presale.payment_mint_count as usize;
+	if index >= MAX_PAYMENT_MINTS
+		|| presale.payment_mint_index(mint_account.key).is_some()
+		|| *mint_account.key == presale.token_for_sale {
+		return Err(PresaleError::InvalidPaymentMint.into());
+	}
+
+	let oracle = match args.oracle {
+		Some(oracle) => {
+			if !oracle.is_enabled() || oracle.program == Pubkey::default() || oracle.max_confidence_bps > 10_000 {
+				return Err(PresaleError::InvalidOracle.into());
+			}
+			oracle
+		},
+		None => {
+			if args.rate_numerator == 0 || args.rate_denominator == 0 {
+				return Err(PresaleError::InvalidPrice.into());
+			}
+			OracleConfig::default()
+		},
+	};
+
+	assert_derivation(
+		program_id,
+		vault_authority,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	let vault_bump = assert_derivation(
+		program_id,
+		vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*mint_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	let mint = Mint::unpack(&mint_account.data.borrow())?;
+
+	create_or_allocate_account_raw(
+		spl_token::id(),
+		vault_account,
+		rent_info,
+		system_account,
+		payer_account,
+		Account::LEN,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*mint_account.key).as_ref(),
+			VAULT.as_bytes(),
+			&[vault_bump],
+		],
+	)?;
+
+	spl_token_init_account(TokenInitAccountParams{
+		account : vault_account.clone(),
+		mint : mint_account.clone(),
+		owner : vault_authority.clone(),
+		rent : rent_info.clone(),
+		token_program : token_program.clone(),
+	})?;
+
+	presale.payment_mints[index] = PaymentMint{
+		mint : *mint_account.key,
+		rate_numerator : args.rate_numerator,
+		rate_denominator : args.rate_denominator,
+		decimals : mint.decimals,
+		total_raised : 0,
+		oracle : oracle,
+		total_refunded : 0,
+	};
+	presale.payment_mint_count = presale.payment_mint_count + 1;
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,Round,MAX_ROUNDS},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		clock::UnixTimestamp,
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct AddRoundArgs {
+	pub start_ts : UnixTimestamp,
+	pub price_numerator : u64,
+	pub price_denominator : u64,
+	pub hardcap : u64,
+	pub min_allocation : u64,
+	pub max_allocation : u64,
+	pub is_whitelist : bool,
+}
+
+pub fn add_round(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : AddRoundArgs,
+	)->ProgramResult{
+	msg!("+ Processing AddRound");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, authority_account)?;
+
+	let index = presale.round_count as usize;
+	if index >= MAX_ROUNDS {
+		return Err(PresaleError::InvalidRound.into());
+	}
+
+	// rounds are appended in start order and can't be scheduled into the past
+	let now = Clock::get()?.unix_timestamp;
+	if args.start_ts <= now || args.start_ts <= presale.rounds[index - 1].start_ts {
+		return Err(PresaleError::InvalidRound.into());
+	}
+
+	if presale.end_ts != 0 && args.start_ts >= presale.end_ts {
+		return Err(PresaleError::InvalidRound.into());
+	}
+
+	let round = Round{
+		start_ts : args.start_ts,
+		price_numerator : args.price_numerator,
+		price_denominator : args.price_denominator,
+		hardcap : args.hardcap,
+		min_allocation : args.min_allocation,
+		max_allocation : args.max_allocation,
+		total_raised : 0,
+		is_whitelist : args.is_whitelist,
+	};
+	if !round.is_valid() {
+		return Err(PresaleError::InvalidRound.into());
+	}
+
+	presale.rounds[index] = round;
+	presale.round_count = presale.round_count + 1;
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
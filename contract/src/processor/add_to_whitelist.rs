@@ -1,7 +1,7 @@
 use crate::{
 	errors::PresaleError,
-	processor::{PresaleData,ClientData},
-	utils::{assert_owned_by,assert_signer,assert_derivation},
+	processor::{PresaleData,ClientData,MAX_ROUNDS,create_client_account,Role},
+	utils::{assert_owned_by,assert_signer,assert_role,assert_derivation},
 	PRESALE,
 };
 
@@ -20,20 +20,86 @@ use {
 	},
 };
 
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct AddToWhitelistArgs {
+	/// bit i whitelists the members for round i
+	pub rounds : u8,
+	/// personal cap for each pair in order, 0 removes it; empty leaves the caps untouched
+	pub allocation_caps : Vec<u64>,
+}
+
+/// Whitelists every (member, client) account pair that follows the fixed accounts
 pub fn add_to_whitelist(
 	program_id : &Pubkey,
 	accounts : &[AccountInfo],
+	args : AddToWhitelistArgs,
 	)->ProgramResult{
 	msg!("Processing AddToWhitelist");
 	let account_iter = &mut accounts.iter();
 	let authority_account = next_account_info(account_iter)?;
-	let member_account = next_account_info(account_iter)?;
+	let payer_account = next_account_info(account_iter)?;
 	let presale_account = next_account_info(account_iter)?;
-	let client_account = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
 
 	assert_owned_by(presale_account,program_id)?;
+
+	let presale=PresaleData::from_account_info(presale_account)?;
+
+	assert_role(&presale, authority_account, Role::WhitelistManager)?;
+	assert_signer(payer_account)?;
+
+	if args.rounds == 0 || (args.rounds as u32) >> MAX_ROUNDS != 0 {
+		return Err(PresaleError::InvalidRound.into());
+	}
+
+	if account_iter.len() == 0 || account_iter.len() % 2 != 0 {
+		return Err(ProgramError::NotEnoughAccountKeys);
+	}
+
+	if !args.allocation_caps.is_empty() && args.allocation_caps.len() != account_iter.len() / 2 {
+		return Err(PresaleError::InvalidAmount.into());
+	}
+
+	let mut index = 0;
+	while let Some(member_account) = account_iter.next() {
+		let client_account = next_account_info(account_iter)?;
+
+		// members don't have to register before they are whitelisted, the payer funds their account
+		if client_account.data_is_empty() {
+			create_client_account(
+				program_id,
+				member_account,
+				presale_account,
+				client_account,
+				payer_account,
+				rent_info,
+				system_account,
+			)?;
+		}
+
+		let mut client = load_member_client(program_id,presale_account,member_account,client_account)?;
+		client.whitelisted_rounds = client.whitelisted_rounds | args.rounds;
+		client.whitelist_revoked = false;
+		if let Some(allocation_cap) = args.allocation_caps.get(index) {
+			client.allocation_cap = *allocation_cap;
+		}
+		client.serialize(&mut *client_account.data.borrow_mut())?;
+		index = index + 1;
+	}
+
+	Ok(())
+}
+
+/// Checks that `client_account` is the client PDA of `member_account` in this presale
+pub fn load_member_client(
+	program_id : &Pubkey,
+	presale_account : &AccountInfo,
+	member_account : &AccountInfo,
+	client_account : &AccountInfo,
+	)->Result<ClientData,ProgramError>{
 	assert_owned_by(client_account,program_id)?;
-	assert_signer(authority_account)?;
 
 	assert_derivation(
 		program_id,
@@ -46,12 +112,7 @@ pub fn add_to_whitelist(
 		],
 	)?;
 
-	let mut presale=PresaleData::from_account_info(presale_account)?;
-	let mut client=ClientData::from_account_info(client_account)?;
-
-	if presale.authority != *authority_account.key {
-		return Err(PresaleError::InvalidAuthority.into());
-	}
+	let client=ClientData::from_account_info(client_account)?;
 
 	if client.owner != *member_account.key {
 		return Err(PresaleError::InvalidClientOwner.into());
@@ -61,12 +122,5 @@ pub fn add_to_whitelist(
 		return Err(PresaleError::InvalidPresaleAccount.into())
 	}
 
-	if presale.is_active == true {
-		return Err(PresaleError::AlreadyStarted.into());
-	}
-
-	client.is_whitelisted = true;
-	client.serialize(&mut *client_account.data.borrow_mut())?;
-
-	Ok(())
+	Ok(client)
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+use crate::{
+	errors::PresaleError,
+	processor::{MultisigData,MultisigProposal},
+	utils::{assert_owned_by,assert_signer,assert_derivation},
+	PRESALE,MULTISIG,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+pub fn approve_multisig(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing ApproveMultisig");
+	let account_iter = &mut accounts.iter();
+	let signer_account = next_account_info(account_iter)?;
+	let multisig_account = next_account_info(account_iter)?;
+	let proposal_account = next_account_info(account_iter)?;
+
+	assert_owned_by(multisig_account, program_id)?;
+	assert_owned_by(proposal_account, program_id)?;
+	assert_signer(signer_account)?;
+
+	let multisig = MultisigData::from_account_info(multisig_account)?;
+	let mut proposal = load_proposal(program_id, multisig_account, proposal_account)?;
+
+	let signer_index = multisig.signer_index(signer_account.key).ok_or(PresaleError::NotMultisigSigner)?;
+	if proposal.approvals & (1 << signer_index) != 0 {
+		return Err(PresaleError::AlreadyApproved.into());
+	}
+
+	proposal.approvals = proposal.approvals | (1 << signer_index);
+	proposal.serialize(&mut *proposal_account.data.borrow_mut())?;
+	Ok(())
+}
+
+/// Loads a proposal of `multisig_account` that is still waiting to be executed
+pub fn load_proposal(
+	program_id : &Pubkey,
+	multisig_account : &AccountInfo,
+	proposal_account : &AccountInfo,
+	)->Result<MultisigProposal,ProgramError>{
+	let proposal = MultisigProposal::from_account_info(proposal_account)?;
+
+	if proposal.multisig != *multisig_account.key {
+		return Err(PresaleError::InvalidMultisig.into());
+	}
+
+	assert_derivation(
+		program_id,
+		proposal_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*multisig_account.key).as_ref(),
+			MULTISIG.as_bytes(),
+			&proposal.index.to_le_bytes(),
+		],
+	)?;
+
+	if proposal.executed {
+		return Err(PresaleError::AlreadyExecuted.into());
+	}
+	Ok(proposal)
+}
\ No newline at end of file
@@ -4,9 +4,10 @@ use crate::{
 	utils::{
 		assert_owned_by,assert_signer,assert_derivation,
 		spl_token_transfer,TokenTransferParams,
-		spl_token_transfer_without_seed,TokenTransferParamsWithoutSeed,
+		sol_transfer,SolTransferParams,
+		Rounding,
 	},
-	PRESALE,
+	PRESALE,VAULT,
 };
 
 use {
@@ -20,16 +21,19 @@ use {
 		program_pack::Pack,
 		pubkey::Pubkey,
 		system_instruction,
+		system_program,
 		sysvar::{clock::Clock,Sysvar},
 	},
 	spl_token::state::Account,
-	std::mem,
+	std::{cmp,mem},
 };
 
 #[repr(C)]
 #[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct BuyArgs {
 	pub amount : u64,
+	/// fill only what is left under the hardcaps instead of failing
+	pub allow_partial : bool,
 }
 
 pub fn buy(
@@ -41,21 +45,33 @@ pub fn buy(
 	let account_iter = &mut accounts.iter();
 	let bidder_account = next_account_info(account_iter)?;
 	let bidder_token_account = next_account_info(account_iter)?;
-	let presale_pot_account = next_account_info(account_iter)?;
+	let raise_vault_account = next_account_info(account_iter)?;
 	let transfer_authority = next_account_info(account_iter)?;
 	let presale_account = next_account_info(account_iter)?;
 	let client_account = next_account_info(account_iter)?;
 	let mint_account = next_account_info(account_iter)?;
 	let token_program = next_account_info(account_iter)?;
+	// system program when paying native SOL, price feed for oracle priced mints
+	let extra_account = account_iter.next();
 
-	assert_owned_by(bidder_token_account,&spl_token::id())?;
-	assert_owned_by(presale_pot_account,&spl_token::id())?;
 	assert_owned_by(mint_account,&spl_token::id())?;
 	assert_owned_by(presale_account,program_id)?;
 	assert_owned_by(client_account,program_id)?;
 	assert_signer(bidder_account)?;
 	assert_signer(transfer_authority)?;
 
+	assert_derivation(
+		program_id,
+		raise_vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*mint_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
 	assert_derivation(
 		program_id,
 		client_account,
@@ -82,52 +98,137 @@ pub fn buy(
 		return Err(PresaleError::NotMatchPresale.into());
 	}
 
-	if *mint_account.key != presale.token_being_raised {
-		return Err(PresaleError::NotMatchTokenAddress.into());
+	let mint_index = presale.payment_mint_index(mint_account.key).ok_or(PresaleError::NotMatchTokenAddress)?;
+	let is_native = presale.is_native() && mint_index == 0;
+
+	if is_native {
+		assert_owned_by(raise_vault_account,&system_program::id())?;
+	} else {
+		assert_owned_by(bidder_token_account,&spl_token::id())?;
+		assert_owned_by(raise_vault_account,&spl_token::id())?;
 	}
 
 //////////////////////////////////////////////////////////////////////////
-	if presale.is_active == false {
+	let now = Clock::get()?.unix_timestamp;
+	if presale.is_ended(now) {
+		return Err(PresaleError::SaleEnded.into());
+	}
+
+	if !presale.is_open(now) {
 		return Err(PresaleError::NotActiveYet.into());
 	}
 
-	if args.amount < presale.min_allocation || args.amount > presale.max_allocation {
+	let round_index = presale.active_round(now).ok_or(PresaleError::NotActiveYet)?;
+	let round = presale.rounds[round_index];
+
+	// limits and totals are kept in units of the token being raised
+	let rate = presale.payment_mints[mint_index].rate(extra_account)?;
+	let mut amount = presale.normalize(mint_index, args.amount, rate, Rounding::Down)?;
+
+	// min_allocation may be 0, a buy worth nothing must not count against the buy limits
+	if amount == 0 || amount < round.min_allocation || amount > round.max_allocation {
 		return Err(PresaleError::InvalidAmount.into());
 	}
 
-	let bidder_token : Account = Account::unpack_from_slice(&bidder_token_account.data.borrow())?;
-	if bidder_token.amount.saturating_sub(args.amount) < 0 {
+	// a fair launch takes everything during the window and scales allocations down after it
+	let mut paid = args.amount;
+	if !presale.is_fair_launch {
+		if presale.total_raised >= presale.hardcap {
+			return Err(PresaleError::HardcapReached.into());
+		}
+
+		if round.total_raised >= round.hardcap {
+			return Err(PresaleError::HardcapReached.into());
+		}
+
+		// the requested amount passed the minimum, so the last buyer may take a smaller remainder
+		let remaining = cmp::min(presale.hardcap - presale.total_raised, round.hardcap - round.total_raised);
+		if amount > remaining {
+			if !args.allow_partial {
+				return Err(PresaleError::WillOverHardcap.into());
+			}
+			paid = presale.denormalize(mint_index, remaining, rate, Rounding::Down)?;
+			amount = presale.normalize(mint_index, paid, rate, Rounding::Down)?;
+			if amount == 0 {
+				return Err(PresaleError::HardcapReached.into());
+			}
+		}
+	}
+
+	let balance = if is_native {
+		bidder_account.lamports()
+	} else {
+		Account::unpack_from_slice(&bidder_token_account.data.borrow())?.amount
+	};
+	if balance < paid {
 		return Err(PresaleError::BalanceTooLow.into());
 	}
 
-	if presale.total_raised > presale.hardcap {
-		return Err(PresaleError::HardcapReached.into());
+	// a personal cap can only tighten the round limit
+	let client_round_amount = client.round_amounts[round_index].checked_add(amount).ok_or(PresaleError::MathOverflow)?;
+	if client.allocation_cap != 0 && client.allocation_cap < round.max_allocation {
+		if client_round_amount > client.allocation_cap {
+			return Err(PresaleError::PersonalCapExceeded.into());
+		}
+	} else if client_round_amount > round.max_allocation {
+		return Err(PresaleError::MoreThanMaxAllocation.into());
 	}
 
-	if (presale.total_raised + args.amount) > presale.hardcap {
-		return Err(PresaleError::WillOverHardcap.into());
+	if presale.is_whitelist_required(now) && round.is_whitelist && !client.is_whitelisted(round_index) {
+		return Err(PresaleError::NotWhitelisted.into());
 	}
 
-	if (client.amount + args.amount) > presale.max_allocation {
-		return Err(PresaleError::MoreThanMaxAllocation.into());
+	let slot = Clock::get()?.slot;
+	let limits = presale.buy_limits;
+	if limits.max_buys_per_client != 0 && client.buy_count >= limits.max_buys_per_client {
+		return Err(PresaleError::TooManyBuys.into());
 	}
 
-	if presale.is_whitelist == true && client.is_whitelisted==false {
-		return Err(PresaleError::NotWhitelisted.into());
+	if limits.min_slots_between_buys != 0 && client.buy_count != 0
+		&& slot < client.last_buy_slot.saturating_add(limits.min_slots_between_buys) {
+		return Err(PresaleError::BuyCooldown.into());
 	}
 
-	//token_transfer
-	spl_token_transfer_without_seed(TokenTransferParamsWithoutSeed{
- 		source : bidder_token_account.clone(),
- 		destination : presale_pot_account.clone(),
- 		authority : transfer_authority.clone(),
- 		token_program : token_program.clone(),
- 		amount : args.amount,
-	})?;
+	let slot_buy_count = if presale.last_buy_slot == slot { presale.slot_buy_count } else { 0 };
+	if limits.max_buys_per_slot != 0 && slot_buy_count >= limits.max_buys_per_slot {
+		return Err(PresaleError::SlotBuyLimitReached.into());
+	}
 
-	presale.total_raised = presale.total_raised + args.amount;
+	if is_native {
+		sol_transfer(SolTransferParams{
+			source : bidder_account.clone(),
+			destination : raise_vault_account.clone(),
+			amount : paid,
+			source_signer_seeds : &[],
+			system_program : extra_account.ok_or(ProgramError::NotEnoughAccountKeys)?.clone(),
+		})?;
+	} else {
+		//token_transfer
+		spl_token_transfer(TokenTransferParams{
+			source : bidder_token_account.clone(),
+			destination : raise_vault_account.clone(),
+			authority : transfer_authority.clone(),
+			authority_signer_seeds : &[],
+			token_program : token_program.clone(),
+			amount : paid,
+		})?;
+	}
 
-	client.amount = client.amount + args.amount;
+	// a fair launch has no hardcap bounding these totals
+	presale.total_raised = presale.total_raised.checked_add(amount).ok_or(PresaleError::MathOverflow)?;
+	presale.rounds[round_index].total_raised = round.total_raised.checked_add(amount).ok_or(PresaleError::MathOverflow)?;
+	presale.payment_mints[mint_index].total_raised = presale.payment_mints[mint_index].total_raised
+		.checked_add(paid).ok_or(PresaleError::MathOverflow)?;
+
+	client.amount = client.amount.checked_add(amount).ok_or(PresaleError::MathOverflow)?;
+	client.round_amounts[round_index] = client_round_amount;
+	client.paid[mint_index] = client.paid[mint_index].checked_add(paid).ok_or(PresaleError::MathOverflow)?;
+	client.buy_count = client.buy_count + 1;
+	client.last_buy_slot = slot;
+	presale.last_buy_slot = slot;
+	presale.slot_buy_count = slot_buy_count + 1;
+
+	msg!("Filled {} of {}", paid, args.amount);
 
 	client.serialize(&mut *client_account.data.borrow_mut())?;
 	presale.serialize(&mut *presale_account.data.borrow_mut())?;
@@ -0,0 +1,45 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+pub fn cancel_proposal(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing CancelProposal");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, authority_account)?;
+
+	if presale.pending_authority == Pubkey::default() {
+		return Err(PresaleError::NoPendingAuthority.into());
+	}
+
+	presale.pending_authority = Pubkey::default();
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
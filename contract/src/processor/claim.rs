@@ -0,0 +1,139 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData},
+	utils::{
+		assert_owned_by,assert_signer,assert_derivation,
+		spl_token_transfer,TokenTransferParams,
+		Rounding,
+	},
+	PRESALE,VAULT,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+	spl_token::state::Account,
+	std::{cmp,mem},
+};
+
+pub fn claim(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing Claim");
+	let account_iter = &mut accounts.iter();
+	let bidder_account = next_account_info(account_iter)?;
+	let bidder_token_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let sale_vault_account = next_account_info(account_iter)?;
+	let client_account = next_account_info(account_iter)?;
+	let mint_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+
+	assert_owned_by(sale_vault_account,&spl_token::id())?;
+	assert_owned_by(bidder_token_account,&spl_token::id())?;
+	assert_owned_by(mint_account,&spl_token::id())?;
+	assert_owned_by(presale_account,program_id)?;
+	assert_owned_by(client_account,program_id)?;
+	assert_signer(bidder_account)?;
+
+	let vault_bump = assert_derivation(
+		program_id,
+		vault_authority,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	assert_derivation(
+		program_id,
+		sale_vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*mint_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	assert_derivation(
+		program_id,
+		client_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*bidder_account.key).as_ref(),
+		],
+	)?;
+
+	if *token_program.key != spl_token::id() {
+		return Err(PresaleError::InvalidTokenProgram.into());
+	}
+
+	let mut presale=PresaleData::from_account_info(presale_account)?;
+	let mut client=ClientData::from_account_info(client_account)?;	
+
+	if client.owner != *bidder_account.key {
+		return Err(PresaleError::InvalidPresaleAccount.into());
+	}
+
+	if client.presale != *presale_account.key {
+		return Err(PresaleError::NotMatchPresale.into());
+	}
+
+	if *mint_account.key != presale.token_for_sale {
+		return Err(PresaleError::NotMatchTokenAddress.into());
+	}
+
+	presale.assert_succeeded()?;
+
+	// the schedule unlocks on its own; the authority can only release faster, never slower
+	let now = Clock::get()?.unix_timestamp;
+	let allocated = presale.allocated_per_round(&client.round_amounts, Rounding::Down)?;
+	let purchased = presale.total_sale_amount(&allocated, Rounding::Down)?;
+	let released = (purchased as u128 * presale.total_percentage_distributed as u128 / 100) as u64;
+	let unlocked = cmp::max(presale.vesting.vested_amount(purchased, now), released);
+	let real_amount = unlocked.saturating_sub(client.claimed);
+	if real_amount == 0 {
+		return Err(PresaleError::NothingToClaim.into());
+	}
+
+	spl_token_transfer(TokenTransferParams{
+		source : sale_vault_account.clone(),
+		destination : bidder_token_account.clone(),
+		authority : vault_authority.clone(),
+		authority_signer_seeds : &[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+			&[vault_bump],
+		],
+		token_program : token_program.clone(),
+		amount : real_amount,
+	})?;
+
+	client.claimed = client.claimed + real_amount;
+	presale.total_claimed = presale.total_claimed + real_amount;
+
+	client.serialize(&mut *client_account.data.borrow_mut())?;
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+
+	Ok(())
+}
\ No newline at end of file
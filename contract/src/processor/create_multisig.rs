@@ -0,0 +1,104 @@
+use crate::{
+	errors::PresaleError,
+	processor::{AccountType,MultisigData,MAX_MULTISIG_SIGNERS,MULTISIG_DATA_SIZE,MULTISIG_DATA_VERSION},
+	utils::{assert_owned_by,assert_signer,assert_derivation,create_or_allocate_account_raw},
+	PRESALE,MULTISIG,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct CreateMultisigArgs {
+	pub threshold : u8,
+	pub signers : Vec<Pubkey>,
+	/// part of the address, lets a creator set up a replacement multisig when a member key is lost
+	pub nonce : u64,
+}
+
+/// The multisig address can then be proposed as the authority of a presale
+pub fn create_multisig(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : CreateMultisigArgs,
+	)->ProgramResult{
+	msg!("+ Processing CreateMultisig");
+	let account_iter = &mut accounts.iter();
+	let creator_account = next_account_info(account_iter)?;
+	let multisig_account = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_signer(creator_account)?;
+
+	let bump = assert_derivation(
+		program_id,
+		multisig_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			MULTISIG.as_bytes(),
+			(*creator_account.key).as_ref(),
+			&args.nonce.to_le_bytes(),
+		],
+	)?;
+
+	if !multisig_account.data_is_empty() {
+		return Err(PresaleError::AlreadyInitialized.into());
+	}
+
+	let signer_count = args.signers.len();
+	if signer_count == 0 || signer_count > MAX_MULTISIG_SIGNERS
+		|| args.threshold == 0 || args.threshold as usize > signer_count {
+		return Err(PresaleError::InvalidMultisig.into());
+	}
+
+	let mut signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+	for (i, signer) in args.signers.iter().enumerate() {
+		if *signer == Pubkey::default() || args.signers[..i].contains(signer) {
+			return Err(PresaleError::InvalidMultisig.into());
+		}
+		signers[i] = *signer;
+	}
+
+	create_or_allocate_account_raw(
+		*program_id,
+		multisig_account,
+		rent_info,
+		system_account,
+		creator_account,
+		MULTISIG_DATA_SIZE,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			MULTISIG.as_bytes(),
+			(*creator_account.key).as_ref(),
+			&args.nonce.to_le_bytes(),
+			&[bump],
+		],
+	)?;
+
+	let multisig = MultisigData{
+		account_type : AccountType::Multisig,
+		version : MULTISIG_DATA_VERSION,
+		threshold : args.threshold,
+		signer_count : signer_count as u8,
+		signers : signers,
+		proposal_count : 0,
+	};
+	multisig.serialize(&mut *multisig_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,88 @@
+use crate::{
+	errors::PresaleError,
+	instruction::PresaleInstruction,
+	processor::{MultisigData,process_instruction},
+	utils::{assert_owned_by,assert_signer,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct ExecuteMultisigArgs {
+	/// number of member accounts following the multisig account
+	pub signer_count : u8,
+	/// serialized PresaleInstruction run with the multisig as signer
+	pub data : Vec<u8>,
+}
+
+/// Runs an admin instruction approved by enough members signing this same transaction
+pub fn execute_multisig(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : ExecuteMultisigArgs,
+	)->ProgramResult{
+	msg!("+ Processing ExecuteMultisig");
+	let account_iter = &mut accounts.iter();
+	let multisig_account = next_account_info(account_iter)?;
+
+	assert_owned_by(multisig_account, program_id)?;
+
+	let multisig = MultisigData::from_account_info(multisig_account)?;
+
+	let signer_count = args.signer_count as usize;
+	if accounts.len() < 1 + signer_count {
+		return Err(ProgramError::NotEnoughAccountKeys);
+	}
+	let (signer_accounts, instruction_accounts) = accounts[1..].split_at(signer_count);
+
+	if !multisig.is_approved(multisig.signed(signer_accounts)) {
+		return Err(PresaleError::NotEnoughApprovals.into());
+	}
+
+	process_as_multisig(program_id, multisig_account.key, instruction_accounts, &args.data)
+}
+
+/// Dispatches `data` with the multisig account marked as signer wherever it appears in `accounts`
+pub fn process_as_multisig<'a>(
+	program_id : &Pubkey,
+	multisig_key : &Pubkey,
+	accounts : &[AccountInfo<'a>],
+	data : &[u8],
+	)->ProgramResult{
+	match PresaleInstruction::try_from_slice(data)? {
+		PresaleInstruction::CreateMultisig(_)
+		| PresaleInstruction::ExecuteMultisig(_)
+		| PresaleInstruction::ProposeMultisig(_)
+		| PresaleInstruction::ApproveMultisig
+		| PresaleInstruction::ExecuteMultisigProposal => {
+			return Err(PresaleError::InvalidMultisigInstruction.into());
+		},
+		_ => {},
+	}
+
+	let accounts : Vec<AccountInfo<'a>> = accounts.iter()
+		.map(|a| {
+			let mut a = a.clone();
+			if *a.key == *multisig_key {
+				a.is_signer = true;
+			}
+			a
+		})
+		.collect();
+	process_instruction(program_id, &accounts, data)
+}
\ No newline at end of file
@@ -0,0 +1,54 @@
+use crate::{
+	errors::PresaleError,
+	processor::{MultisigData,MultisigProposal,load_proposal,process_as_multisig},
+	utils::{assert_owned_by,assert_signer,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+/// Anyone can execute a proposal once it has enough approvals
+pub fn execute_multisig_proposal(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing ExecuteMultisigProposal");
+	let account_iter = &mut accounts.iter();
+	let multisig_account = next_account_info(account_iter)?;
+	let proposal_account = next_account_info(account_iter)?;
+	let instruction_accounts = account_iter.as_slice();
+
+	assert_owned_by(multisig_account, program_id)?;
+	assert_owned_by(proposal_account, program_id)?;
+
+	let multisig = MultisigData::from_account_info(multisig_account)?;
+	let mut proposal = load_proposal(program_id, multisig_account, proposal_account)?;
+
+	if !multisig.is_approved(proposal.approvals) {
+		return Err(PresaleError::NotEnoughApprovals.into());
+	}
+
+	if instruction_accounts.len() != proposal.accounts.len()
+		|| instruction_accounts.iter().zip(proposal.accounts.iter()).any(|(a, key)| a.key != key) {
+		return Err(PresaleError::ProposalAccountsMismatch.into());
+	}
+
+	// marked first so the instruction can't run twice
+	proposal.executed = true;
+	proposal.serialize(&mut *proposal_account.data.borrow_mut())?;
+
+	process_as_multisig(program_id, multisig_account.key, instruction_accounts, &proposal.data)
+}
\ No newline at end of file
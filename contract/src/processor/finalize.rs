@@ -0,0 +1,57 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,PresaleStatus},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+pub fn finalize(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing Finalize");
+	let account_iter = &mut accounts.iter();
+	let caller_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+	assert_signer(caller_account)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	if presale.status != PresaleStatus::Pending {
+		return Err(PresaleError::AlreadyFinalized.into());
+	}
+
+	// anyone can settle a sale once its window has ended, a manual stop only counts for the authority
+	let now = Clock::get()?.unix_timestamp;
+	if !presale.is_ended(now) {
+		assert_authority(&presale, caller_account)?;
+		if !presale.is_closed(now) {
+			return Err(PresaleError::StillActive.into());
+		}
+	}
+
+	presale.status = if presale.total_raised >= presale.softcap {
+		PresaleStatus::Succeeded
+	} else {
+		PresaleStatus::Failed
+	};
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
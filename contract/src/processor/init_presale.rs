@@ -1,14 +1,19 @@
 use crate::{
 	errors::PresaleError,
-	processor::{PresaleData,ClientData},
-	utils::{assert_owned_by,assert_signer,assert_derivation},
-	PRESALE,
+	processor::{AccountType,PresaleData,ClientData,BuyLimits,OracleConfig,PaymentMint,PresaleStatus,Round,Vesting,MAX_PAYMENT_MINTS,MAX_ROUNDS,PRESALE_DATA_SIZE,PRESALE_DATA_VERSION,PRESALE_DATA_RESERVED},
+	utils::{
+		assert_owned_by,assert_signer,assert_derivation,create_or_allocate_account_raw,
+		spl_token_init_account,TokenInitAccountParams,
+		sol_transfer,SolTransferParams,
+	},
+	PRESALE,VAULT,
 };
 
 use {
 	borsh::{BorshDeserialize,BorshSerialize},
 	solana_program::{
 		account_info::{next_account_info,AccountInfo},
+		clock::UnixTimestamp,
 		entrypoint::ProgramResult,
 		msg,
 		program::invoke_signed,
@@ -16,33 +21,297 @@ use {
 		program_pack::Pack,
 		pubkey::Pubkey,
 		system_instruction,
-		sysvar::{clock::Clock,Sysvar},
+		sysvar::{clock::Clock,rent::Rent,Sysvar},
 	},
+	spl_token::state::{Account,Mint},
 };
 
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct InitPresaleArgs {
+	pub min_allocation : u64,
+	pub max_allocation : u64,
+	pub hardcap : u64,
+	pub softcap : u64,
+	pub price_numerator : u64,
+	pub price_denominator : u64,
+	pub vesting : Vesting,
+	pub start_ts : UnixTimestamp,
+	pub end_ts : UnixTimestamp,
+	pub whitelist_end_ts : UnixTimestamp,
+	pub fair_launch : bool,
+	pub buy_limits : BuyLimits,
+}
+
 pub fn init_presale(
 	program_id : &Pubkey,
 	accounts : &[AccountInfo],
+	args : InitPresaleArgs,
 	)->ProgramResult{
-	msg!("+ Processing StartPresale");
+	msg!("+ Processing InitPresale");
 	let account_iter = &mut accounts.iter();
 	let authority_account = next_account_info(account_iter)?;
 	let presale_account = next_account_info(account_iter)?;
+	let token_for_sale_mint = next_account_info(account_iter)?;
+	let token_being_raised_mint = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let sale_vault_account = next_account_info(account_iter)?;
+	let raise_vault_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
 
-	assert_owned_by(presale_account, program_id)?;
+	assert_owned_by(token_for_sale_mint,&spl_token::id())?;
+	assert_owned_by(token_being_raised_mint,&spl_token::id())?;
 	assert_signer(authority_account)?;
 
-	let mut presale = PresaleData::from_account_info(presale_account)?;
+	let bump = assert_derivation(
+		program_id,
+		presale_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*authority_account.key).as_ref(),
+			(*token_for_sale_mint.key).as_ref(),
+		],
+	)?;
+
+	if *token_program.key != spl_token::id() {
+		return Err(PresaleError::InvalidTokenProgram.into());
+	}
+
+	if !presale_account.data_is_empty() {
+		return Err(PresaleError::AlreadyInitialized.into());
+	}
+
+	let sale_mint = Mint::unpack(&token_for_sale_mint.data.borrow())?;
+	let raise_mint = Mint::unpack(&token_being_raised_mint.data.borrow())?;
 
-	if presale.authority != *authority_account.key {
-		return Err(PresaleError::InvalidAuthority.into());
+	if args.min_allocation > args.max_allocation || args.max_allocation == 0 || args.hardcap == 0
+		|| args.softcap > args.hardcap {
+		return Err(PresaleError::InvalidAmount.into());
 	}
 
-	if presale.is_active ==true {
-		return Err(PresaleError::AlreadyStarted.into());
+	if args.price_numerator == 0 || args.price_denominator == 0 {
+		return Err(PresaleError::InvalidPrice.into());
 	}
 
-	presale.is_active=true;
+	if !args.vesting.is_valid() {
+		return Err(PresaleError::InvalidVesting.into());
+	}
+
+	// with a softcap buyers must be able to finalize a failed sale and refund without the authority,
+	// which needs a deadline
+	if args.start_ts < 0 || args.end_ts < 0 || args.whitelist_end_ts < 0
+		|| (args.end_ts != 0 && args.end_ts <= args.start_ts)
+		|| (args.softcap > 0 && args.end_ts == 0) {
+		return Err(PresaleError::InvalidSaleWindow.into());
+	}
+
+	create_or_allocate_account_raw(
+		*program_id,
+		presale_account,
+		rent_info,
+		system_account,
+		authority_account,
+		PRESALE_DATA_SIZE,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*authority_account.key).as_ref(),
+			(*token_for_sale_mint.key).as_ref(),
+			&[bump],
+		],
+	)?;
+
+	create_presale_vaults(
+		program_id,
+		authority_account,
+		presale_account,
+		token_for_sale_mint,
+		token_being_raised_mint,
+		vault_authority,
+		sale_vault_account,
+		raise_vault_account,
+		token_program,
+		rent_info,
+		system_account,
+	)?;
+
+	// the first round is configured here, later ones through AddRound
+	let mut rounds = [Round::default(); MAX_ROUNDS];
+	rounds[0] = Round{
+		start_ts : 0,
+		price_numerator : args.price_numerator,
+		price_denominator : args.price_denominator,
+		hardcap : args.hardcap,
+		min_allocation : args.min_allocation,
+		max_allocation : args.max_allocation,
+		total_raised : 0,
+		is_whitelist : true,
+	};
+
+	let mut payment_mints = [PaymentMint::default(); MAX_PAYMENT_MINTS];
+	payment_mints[0] = PaymentMint{
+		mint : *token_being_raised_mint.key,
+		rate_numerator : 1,
+		rate_denominator : 1,
+		decimals : raise_mint.decimals,
+		total_raised : 0,
+		oracle : OracleConfig::default(),
+		total_refunded : 0,
+	};
+
+	let presale = PresaleData{
+		account_type : AccountType::Presale,
+		version : PRESALE_DATA_VERSION,
+		authority : *authority_account.key,
+		token_for_sale : *token_for_sale_mint.key,
+		token_being_raised : *token_being_raised_mint.key,
+		hardcap : args.hardcap,
+		softcap : args.softcap,
+		sale_decimals : sale_mint.decimals,
+		raise_decimals : raise_mint.decimals,
+		total_raised : 0,
+		total_percentage_distributed : 0,
+		total_claimed : 0,
+		// a scheduled sale opens on its own, StopPresale stays available as an override
+		is_active : args.start_ts != 0,
+		is_whitelist : true,
+		status : PresaleStatus::Pending,
+		vesting : args.vesting,
+		start_ts : args.start_ts,
+		end_ts : args.end_ts,
+		whitelist_end_ts : args.whitelist_end_ts,
+		round_count : 1,
+		rounds : rounds,
+		merkle_root : [0; 32],
+		payment_mint_count : 1,
+		payment_mints : payment_mints,
+		is_fair_launch : args.fair_launch,
+		buy_limits : args.buy_limits,
+		last_buy_slot : 0,
+		slot_buy_count : 0,
+		pending_authority : Pubkey::default(),
+		whitelist_manager : Pubkey::default(),
+		pauser : Pubkey::default(),
+		treasurer : Pubkey::default(),
+		reserved : [0; PRESALE_DATA_RESERVED],
+	};
 	presale.serialize(&mut *presale_account.data.borrow_mut())?;
 	Ok(())
+}
+
+/// Creates the escrow accounts of a presale: a token account for the sale and one for the raise,
+/// or a lamport vault when native SOL is raised. `payer_account` funds the rent
+pub fn create_presale_vaults<'a>(
+	program_id : &Pubkey,
+	payer_account : &AccountInfo<'a>,
+	presale_account : &AccountInfo<'a>,
+	token_for_sale_mint : &AccountInfo<'a>,
+	token_being_raised_mint : &AccountInfo<'a>,
+	vault_authority : &AccountInfo<'a>,
+	sale_vault_account : &AccountInfo<'a>,
+	raise_vault_account : &AccountInfo<'a>,
+	token_program : &AccountInfo<'a>,
+	rent_info : &AccountInfo<'a>,
+	system_account : &AccountInfo<'a>,
+	)->ProgramResult{
+	assert_derivation(
+		program_id,
+		vault_authority,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	let sale_vault_bump = assert_derivation(
+		program_id,
+		sale_vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*token_for_sale_mint.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	let raise_vault_bump = assert_derivation(
+		program_id,
+		raise_vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*token_being_raised_mint.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	create_or_allocate_account_raw(
+		spl_token::id(),
+		sale_vault_account,
+		rent_info,
+		system_account,
+		payer_account,
+		Account::LEN,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*token_for_sale_mint.key).as_ref(),
+			VAULT.as_bytes(),
+			&[sale_vault_bump],
+		],
+	)?;
+
+	spl_token_init_account(TokenInitAccountParams{
+		account : sale_vault_account.clone(),
+		mint : token_for_sale_mint.clone(),
+		owner : vault_authority.clone(),
+		rent : rent_info.clone(),
+		token_program : token_program.clone(),
+	})?;
+
+	if *token_being_raised_mint.key == spl_token::native_mint::id() {
+		// native SOL is kept as lamports in the vault itself, seeded with its rent exemption
+		let rent = &Rent::from_account_info(rent_info)?;
+		sol_transfer(SolTransferParams{
+			source : payer_account.clone(),
+			destination : raise_vault_account.clone(),
+			amount : rent.minimum_balance(0).saturating_sub(raise_vault_account.lamports()),
+			source_signer_seeds : &[],
+			system_program : system_account.clone(),
+		})?;
+	} else {
+		create_or_allocate_account_raw(
+			spl_token::id(),
+			raise_vault_account,
+			rent_info,
+			system_account,
+			payer_account,
+			Account::LEN,
+			&[
+				PRESALE.as_bytes(),
+				program_id.as_ref(),
+				(*presale_account.key).as_ref(),
+				(*token_being_raised_mint.key).as_ref(),
+				VAULT.as_bytes(),
+				&[raise_vault_bump],
+			],
+		)?;
+
+		spl_token_init_account(TokenInitAccountParams{
+			account : raise_vault_account.clone(),
+			mint : token_being_raised_mint.clone(),
+			owner : vault_authority.clone(),
+			rent : rent_info.clone(),
+			token_program : token_program.clone(),
+		})?;
+	}
+	Ok(())
 }
\ No newline at end of file
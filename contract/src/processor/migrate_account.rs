@@ -0,0 +1,356 @@
+use crate::{
+	errors::PresaleError,
+	processor::{
+		AccountType,PresaleData,ClientData,OracleConfig,PaymentMint,PresaleStatus,Round,Vesting,BuyLimits,
+		create_presale_vaults,ACCOUNT_HEADER_SIZE,MAX_ROUNDS,MAX_PAYMENT_MINTS,
+		PRESALE_DATA_SIZE,PRESALE_DATA_VERSION,PRESALE_DATA_RESERVED,
+		CLIENT_DATA_SIZE,CLIENT_DATA_VERSION,CLIENT_DATA_RESERVED,
+	},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation,sol_transfer,SolTransferParams,Rounding},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		borsh::try_from_slice_unchecked,
+		entrypoint::ProgramResult,
+		msg,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		sysvar::{rent::Rent,Sysvar},
+	},
+	spl_token::state::Mint,
+	std::convert::TryInto,
+};
+
+/// Layout of version 0: accounts from before the header, with an f64 price and no escrow vaults
+pub const LEGACY_PRESALE_DATA_SIZE : usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct LegacyPresaleData{
+    pub authority : Pubkey,
+    pub token_for_sale : Pubkey,
+    pub token_being_raised : Pubkey,
+    pub min_allocation : u64,
+    pub max_allocation : u64,
+    pub hardcap : u64,
+    /// base units sold per base unit raised
+    pub token_per_usd : f64,
+    pub total_raised : u64,
+    pub total_percentage_distributed : u64,
+    pub is_active : bool,
+    pub is_whitelist : bool,
+}
+
+pub const LEGACY_CLIENT_DATA_SIZE : usize = 32 + 32 + 8 + 1 + 1;
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct LegacyClientData{
+    pub owner : Pubkey,
+    pub presale : Pubkey,
+    pub amount : u64,
+    pub is_whitelisted : bool,
+    pub already_paid : bool,
+}
+
+/// Sizes of version 1, which had the header but no reserved space
+pub const PRESALE_DATA_V1_SIZE : usize = 1266;
+pub const CLIENT_DATA_V1_SIZE : usize = 172;
+
+/// The legacy f64 price is kept with 9 decimals of precision
+const LEGACY_PRICE_SCALE : u64 = 1_000_000_000;
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct MigrateAccountArgs {
+	pub account_type : AccountType,
+}
+
+/// Upgrades an account to the current layout, the signer pays for the extra space and must control the account:
+/// the authority of a presale or the owner of a client.
+/// Accounts: signer, account, system program, then for a version 0 presale its two mints, vault authority,
+/// sale vault, raise vault, token program and rent sysvar, and for a version 0 client its presale,
+/// which has to be migrated first
+pub fn migrate_account(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : MigrateAccountArgs,
+	)->ProgramResult{
+	msg!("+ Processing MigrateAccount");
+	let account_iter = &mut accounts.iter();
+	let signer_account = next_account_info(account_iter)?;
+	let account = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_owned_by(account, program_id)?;
+	assert_signer(signer_account)?;
+
+	let (size, version) = match args.account_type {
+		AccountType::Presale => (PRESALE_DATA_SIZE, PRESALE_DATA_VERSION),
+		AccountType::Client => (CLIENT_DATA_SIZE, CLIENT_DATA_VERSION),
+		_ => return Err(PresaleError::DataTypeMismatch.into()),
+	};
+
+	let old = account.data.borrow().to_vec();
+	let has_header = |v : u8| old.len() >= ACCOUNT_HEADER_SIZE && old[0] == args.account_type as u8 && old[1] == v;
+	if old.len() >= size && has_header(version) {
+		return Err(PresaleError::NothingToMigrate.into());
+	}
+
+	let mut data = vec![0u8; size];
+	match (args.account_type, old.len()) {
+		(AccountType::Presale, LEGACY_PRESALE_DATA_SIZE) => {
+			let legacy : LegacyPresaleData = try_from_slice_unchecked(&old)?;
+			if legacy.authority != *signer_account.key {
+				return Err(PresaleError::InvalidAuthority.into());
+			}
+			let presale = migrate_legacy_presale(program_id, signer_account, account, system_account, account_iter, legacy)?;
+			presale.serialize(&mut data.as_mut_slice())?;
+		},
+		(AccountType::Presale, PRESALE_DATA_V1_SIZE) if has_header(1) => {
+			data[..old.len()].copy_from_slice(&old);
+			data[1] = version;
+			let presale : PresaleData = try_from_slice_unchecked(&data)?;
+			assert_authority(&presale, signer_account)?;
+		},
+		(AccountType::Client, LEGACY_CLIENT_DATA_SIZE) => {
+			let legacy : LegacyClientData = try_from_slice_unchecked(&old)?;
+			assert_client(program_id, signer_account, account, &legacy.owner, &legacy.presale)?;
+			let presale_account = next_account_info(account_iter)?;
+			let client = migrate_legacy_client(program_id, presale_account, legacy)?;
+			client.serialize(&mut data.as_mut_slice())?;
+		},
+		(AccountType::Client, CLIENT_DATA_V1_SIZE) if has_header(1) => {
+			data[..old.len()].copy_from_slice(&old);
+			data[1] = version;
+			let client : ClientData = try_from_slice_unchecked(&data)?;
+			assert_client(program_id, signer_account, account, &client.owner, &client.presale)?;
+		},
+		_ => return Err(PresaleError::DataTypeMismatch.into()),
+	}
+
+	let rent = Rent::get()?;
+	let required_lamports = rent.minimum_balance(size).saturating_sub(account.lamports());
+	if required_lamports > 0 {
+		sol_transfer(SolTransferParams{
+			source : signer_account.clone(),
+			destination : account.clone(),
+			amount : required_lamports,
+			source_signer_seeds : &[],
+			system_program : system_account.clone(),
+		})?;
+	}
+
+	account.realloc(size, false)?;
+	account.data.borrow_mut().copy_from_slice(&data);
+	Ok(())
+}
+
+fn assert_client(
+	program_id : &Pubkey,
+	signer_account : &AccountInfo,
+	client_account : &AccountInfo,
+	owner : &Pubkey,
+	presale : &Pubkey,
+	)->ProgramResult{
+	if *owner != *signer_account.key {
+		return Err(PresaleError::InvalidClientOwner.into());
+	}
+	assert_derivation(
+		program_id,
+		client_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			presale.as_ref(),
+			owner.as_ref(),
+		],
+	)?;
+	Ok(())
+}
+
+/// Version 0 presales sent funds to an account of the authority's choosing, so the escrow vaults
+/// are created empty here; what was raised before stays where the old program sent it
+fn migrate_legacy_presale<'a>(
+	program_id : &Pubkey,
+	signer_account : &AccountInfo<'a>,
+	presale_account : &AccountInfo<'a>,
+	system_account : &AccountInfo<'a>,
+	account_iter : &mut std::slice::Iter<AccountInfo<'a>>,
+	legacy : LegacyPresaleData,
+	)->Result<PresaleData,ProgramError>{
+	let token_for_sale_mint = next_account_info(account_iter)?;
+	let token_being_raised_mint = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let sale_vault_account = next_account_info(account_iter)?;
+	let raise_vault_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+
+	if *token_for_sale_mint.key != legacy.token_for_sale || *token_being_raised_mint.key != legacy.token_being_raised {
+		return Err(PresaleError::NotMatchTokenAddress.into());
+	}
+	assert_owned_by(token_for_sale_mint,&spl_token::id())?;
+	assert_owned_by(token_being_raised_mint,&spl_token::id())?;
+
+	if *token_program.key != spl_token::id() {
+		return Err(PresaleError::InvalidTokenProgram.into());
+	}
+
+	let sale_mint = Mint::unpack(&token_for_sale_mint.data.borrow())?;
+	let raise_mint = Mint::unpack(&token_being_raised_mint.data.borrow())?;
+	let (price_numerator, price_denominator) = legacy_price(legacy.token_per_usd, raise_mint.decimals, sale_mint.decimals)?;
+
+	create_presale_vaults(
+		program_id,
+		signer_account,
+		presale_account,
+		token_for_sale_mint,
+		token_being_raised_mint,
+		vault_authority,
+		sale_vault_account,
+		raise_vault_account,
+		token_program,
+		rent_info,
+		system_account,
+	)?;
+
+	// the single legacy price and allocation limits become the first round
+	let mut rounds = [Round::default(); MAX_ROUNDS];
+	rounds[0] = Round{
+		start_ts : 0,
+		price_numerator : price_numerator,
+		price_denominator : price_denominator,
+		hardcap : legacy.hardcap,
+		min_allocation : legacy.min_allocation,
+		max_allocation : legacy.max_allocation,
+		total_raised : legacy.total_raised,
+		is_whitelist : true,
+	};
+
+	let mut payment_mints = [PaymentMint::default(); MAX_PAYMENT_MINTS];
+	payment_mints[0] = PaymentMint{
+		mint : legacy.token_being_raised,
+		rate_numerator : 1,
+		rate_denominator : 1,
+		decimals : raise_mint.decimals,
+		total_raised : legacy.total_raised,
+		oracle : OracleConfig::default(),
+		total_refunded : 0,
+	};
+
+	Ok(PresaleData{
+		account_type : AccountType::Presale,
+		version : PRESALE_DATA_VERSION,
+		authority : legacy.authority,
+		token_for_sale : legacy.token_for_sale,
+		token_being_raised : legacy.token_being_raised,
+		hardcap : legacy.hardcap,
+		softcap : 0,
+		sale_decimals : sale_mint.decimals,
+		raise_decimals : raise_mint.decimals,
+		total_raised : legacy.total_raised,
+		// legacy distributions were paid from the authority's wallet, not from the sale vault
+		total_percentage_distributed : 0,
+		total_claimed : 0,
+		is_active : legacy.is_active,
+		is_whitelist : legacy.is_whitelist,
+		status : PresaleStatus::Pending,
+		vesting : Vesting::default(),
+		start_ts : 0,
+		end_ts : 0,
+		whitelist_end_ts : 0,
+		round_count : 1,
+		rounds : rounds,
+		merkle_root : [0; 32],
+		payment_mint_count : 1,
+		payment_mints : payment_mints,
+		is_fair_launch : false,
+		buy_limits : BuyLimits::default(),
+		last_buy_slot : 0,
+		slot_buy_count : 0,
+		pending_authority : Pubkey::default(),
+		whitelist_manager : Pubkey::default(),
+		pauser : Pubkey::default(),
+		treasurer : Pubkey::default(),
+		reserved : [0; PRESALE_DATA_RESERVED],
+	})
+}
+
+/// A client the old DistributeToken already paid is treated as fully claimed, since what it received
+/// came from outside the vault and was not recorded
+fn migrate_legacy_client(
+	program_id : &Pubkey,
+	presale_account : &AccountInfo,
+	legacy : LegacyClientData,
+	)->Result<ClientData,ProgramError>{
+	if *presale_account.key != legacy.presale {
+		return Err(PresaleError::NotMatchPresale.into());
+	}
+	assert_owned_by(presale_account, program_id)?;
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	let mut round_amounts = [0; MAX_ROUNDS];
+	round_amounts[0] = legacy.amount;
+	let mut paid = [0; MAX_PAYMENT_MINTS];
+	paid[0] = legacy.amount;
+
+	let claimed = if legacy.already_paid {
+		presale.sale_amount(0, legacy.amount, Rounding::Down)?
+	} else {
+		0
+	};
+	if claimed != 0 {
+		// keeps WithdrawUnsoldTokens from reserving tokens nobody will claim from the vault
+		presale.total_claimed = presale.total_claimed.checked_add(claimed).ok_or(PresaleError::MathOverflow)?;
+		presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	}
+
+	Ok(ClientData{
+		account_type : AccountType::Client,
+		version : CLIENT_DATA_VERSION,
+		owner : legacy.owner,
+		presale : legacy.presale,
+		amount : legacy.amount,
+		round_amounts : round_amounts,
+		// legacy sales had one round, bit 0
+		whitelisted_rounds : if legacy.is_whitelisted { 1 } else { 0 },
+		claimed : claimed,
+		allocation_cap : 0,
+		paid : paid,
+		excess_refunded : 0,
+		buy_count : 0,
+		last_buy_slot : 0,
+		whitelist_revoked : false,
+		reserved : [0; CLIENT_DATA_RESERVED],
+	})
+}
+
+/// `token_per_usd` base units sold per base unit raised, as whole tokens sold per whole token raised
+pub fn legacy_price(token_per_usd : f64, raise_decimals : u8, sale_decimals : u8) -> Result<(u64, u64),ProgramError> {
+	if !token_per_usd.is_finite() || token_per_usd <= 0.0 {
+		return Err(PresaleError::InvalidPrice.into());
+	}
+	let overflow = || -> ProgramError { PresaleError::MathOverflow.into() };
+	let scaled = token_per_usd * LEGACY_PRICE_SCALE as f64 + 0.5;
+	if scaled < 1.0 || scaled >= u64::MAX as f64 {
+		return Err(PresaleError::InvalidPrice.into());
+	}
+
+	let mut numerator = (scaled as u128)
+		.checked_mul(10u128.pow(raise_decimals as u32)).ok_or_else(overflow)?;
+	let mut denominator = (LEGACY_PRICE_SCALE as u128)
+		.checked_mul(10u128.pow(sale_decimals as u32)).ok_or_else(overflow)?;
+	let (mut a, mut b) = (numerator, denominator);
+	while b != 0 {
+		let r = a % b;
+		a = b;
+		b = r;
+	}
+	numerator = numerator / a;
+	denominator = denominator / a;
+	Ok((numerator.try_into().map_err(|_| overflow())?, denominator.try_into().map_err(|_| overflow())?))
+}
\ No newline at end of file
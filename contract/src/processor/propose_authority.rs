@@ -0,0 +1,47 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+/// The new key only takes over once it signs AcceptAuthority
+pub fn propose_authority(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing ProposeAuthority");
+	let account_iter = &mut accounts.iter();
+	let old_authority_account = next_account_info(account_iter)?;
+	let new_authority_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, old_authority_account)?;
+
+	if *new_authority_account.key == Pubkey::default() || *new_authority_account.key == presale.authority {
+		return Err(PresaleError::InvalidAuthority.into());
+	}
+
+	presale.pending_authority = *new_authority_account.key;
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
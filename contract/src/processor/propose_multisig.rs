@@ -0,0 +1,97 @@
+use crate::{
+	errors::PresaleError,
+	processor::{AccountType,MultisigData,MultisigProposal,MULTISIG_PROPOSAL_VERSION},
+	utils::{assert_owned_by,assert_signer,assert_derivation,create_or_allocate_account_raw},
+	PRESALE,MULTISIG,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct ProposeMultisigArgs {
+	/// accounts the instruction will be executed with, in order
+	pub accounts : Vec<Pubkey>,
+	/// serialized PresaleInstruction
+	pub data : Vec<u8>,
+}
+
+/// Stores an admin instruction for the other members to approve, the proposer's approval included
+pub fn propose_multisig(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : ProposeMultisigArgs,
+	)->ProgramResult{
+	msg!("+ Processing ProposeMultisig");
+	let account_iter = &mut accounts.iter();
+	let proposer_account = next_account_info(account_iter)?;
+	let multisig_account = next_account_info(account_iter)?;
+	let proposal_account = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_owned_by(multisig_account, program_id)?;
+	assert_signer(proposer_account)?;
+
+	let mut multisig = MultisigData::from_account_info(multisig_account)?;
+	let signer_index = multisig.signer_index(proposer_account.key).ok_or(PresaleError::NotMultisigSigner)?;
+
+	let index = multisig.proposal_count;
+	let bump = assert_derivation(
+		program_id,
+		proposal_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*multisig_account.key).as_ref(),
+			MULTISIG.as_bytes(),
+			&index.to_le_bytes(),
+		],
+	)?;
+
+	create_or_allocate_account_raw(
+		*program_id,
+		proposal_account,
+		rent_info,
+		system_account,
+		proposer_account,
+		MultisigProposal::size(args.accounts.len(), args.data.len()),
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*multisig_account.key).as_ref(),
+			MULTISIG.as_bytes(),
+			&index.to_le_bytes(),
+			&[bump],
+		],
+	)?;
+
+	let proposal = MultisigProposal{
+		account_type : AccountType::MultisigProposal,
+		version : MULTISIG_PROPOSAL_VERSION,
+		multisig : *multisig_account.key,
+		index : index,
+		approvals : 1 << signer_index,
+		executed : false,
+		accounts : args.accounts,
+		data : args.data,
+	};
+	proposal.serialize(&mut *proposal_account.data.borrow_mut())?;
+
+	multisig.proposal_count = index + 1;
+	multisig.serialize(&mut *multisig_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
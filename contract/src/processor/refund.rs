@@ -0,0 +1,121 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,PresaleStatus,MAX_ROUNDS,transfer_from_raise_vault},
+	utils::{
+		assert_owned_by,assert_signer,assert_derivation,
+		spl_token_transfer,TokenTransferParams,
+		Rounding,
+	},
+	PRESALE,VAULT,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+	spl_token::state::Account,
+	std::{cmp,mem},
+};
+
+pub fn refund(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing Refund");
+	let account_iter = &mut accounts.iter();
+	let bidder_account = next_account_info(account_iter)?;
+	let bidder_token_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let raise_vault_account = next_account_info(account_iter)?;
+	let client_account = next_account_info(account_iter)?;
+	let mint_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+	let system_account = account_iter.next();
+
+	assert_owned_by(mint_account,&spl_token::id())?;
+	assert_owned_by(presale_account,program_id)?;
+	assert_owned_by(client_account,program_id)?;
+	assert_signer(bidder_account)?;
+
+	assert_derivation(
+		program_id,
+		client_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*bidder_account.key).as_ref(),
+		],
+	)?;
+
+	let mut presale=PresaleData::from_account_info(presale_account)?;
+	let mut client=ClientData::from_account_info(client_account)?;	
+
+	if client.owner != *bidder_account.key {
+		return Err(PresaleError::InvalidPresaleAccount.into());
+	}
+
+	if client.presale != *presale_account.key {
+		return Err(PresaleError::NotMatchPresale.into());
+	}
+
+	let mint_index = presale.payment_mint_index(mint_account.key).ok_or(PresaleError::NotMatchTokenAddress)?;
+
+	// each deposit goes back in the mint it was paid with, in full after a failed sale
+	// or only its unfilled part after an oversubscribed fair launch
+	let refund_amount = match presale.status {
+		PresaleStatus::Failed => client.paid[mint_index],
+		PresaleStatus::Succeeded if presale.is_oversubscribed() => {
+			if client.is_excess_refunded(mint_index) {
+				0
+			} else {
+				presale.excess(client.paid[mint_index], Rounding::Down)?
+			}
+		},
+		_ => return Err(PresaleError::NotRefundable.into()),
+	};
+	if refund_amount == 0 {
+		return Err(PresaleError::NothingToRefund.into());
+	}
+
+	// with native SOL the bidder's wallet is passed as the token account
+	transfer_from_raise_vault(
+		program_id,
+		&presale,
+		presale_account,
+		mint_account.key,
+		vault_authority,
+		raise_vault_account,
+		bidder_token_account,
+		token_program,
+		system_account,
+		refund_amount,
+		0,
+	)?;
+
+	if presale.status == PresaleStatus::Failed {
+		client.paid[mint_index] = 0;
+		if client.paid.iter().all(|paid| *paid == 0) {
+			client.amount = 0;
+			client.round_amounts = [0; MAX_ROUNDS];
+		}
+	} else {
+		// paid stays as deposited, the filled part is derived from it at claim time
+		client.excess_refunded = client.excess_refunded | (1 << mint_index);
+		presale.payment_mints[mint_index].total_refunded = presale.payment_mints[mint_index].total_refunded + refund_amount;
+		presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	}
+	client.serialize(&mut *client_account.data.borrow_mut())?;
+
+	Ok(())
+}
\ No newline at end of file
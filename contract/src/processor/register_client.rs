@@ -0,0 +1,178 @@
+use crate::{
+	errors::PresaleError,
+	processor::{AccountType,PresaleData,ClientData,CLIENT_DATA_SIZE,CLIENT_DATA_VERSION,CLIENT_DATA_RESERVED,MAX_ROUNDS,MAX_PAYMENT_MINTS},
+	merkle::{hash_leaf,verify},
+	utils::{assert_owned_by,assert_signer,assert_derivation,create_or_allocate_account_raw},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct WhitelistProof {
+	pub rounds : u8,
+	pub allocation_cap : u64,
+	pub proof : Vec<[u8; 32]>,
+}
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct RegisterClientArgs {
+	pub whitelist_proof : Option<WhitelistProof>,
+}
+
+pub fn register_client(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : RegisterClientArgs,
+	)->ProgramResult{
+	msg!("+ Processing RegisterClient");
+	let account_iter = &mut accounts.iter();
+	let owner_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let client_account = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+	assert_signer(owner_account)?;
+
+	let presale = PresaleData::from_account_info(presale_account)?;
+
+	// an already registered client can come back with a proof, but not register twice
+	if client_account.data_is_empty() {
+		create_client_account(
+			program_id,
+			owner_account,
+			presale_account,
+			client_account,
+			owner_account,
+			rent_info,
+			system_account,
+		)?;
+	} else if args.whitelist_proof.is_none() {
+		return Err(PresaleError::AlreadyInitialized.into());
+	}
+
+	let whitelist_proof = match args.whitelist_proof {
+		Some(whitelist_proof) => whitelist_proof,
+		None => return Ok(()),
+	};
+
+	assert_owned_by(client_account, program_id)?;
+	assert_derivation(
+		program_id,
+		client_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*owner_account.key).as_ref(),
+		],
+	)?;
+
+	let mut client = ClientData::from_account_info(client_account)?;
+
+	if client.owner != *owner_account.key {
+		return Err(PresaleError::InvalidClientOwner.into());
+	}
+
+	if client.presale != *presale_account.key {
+		return Err(PresaleError::NotMatchPresale.into());
+	}
+
+	// the merkle root can't drop a single member, RemoveFromWhitelist revokes the proof instead
+	if client.whitelist_revoked {
+		return Err(PresaleError::WhitelistRevoked.into());
+	}
+
+	let leaf = hash_leaf(owner_account.key, whitelist_proof.rounds, whitelist_proof.allocation_cap);
+	if presale.merkle_root == [0; 32] || !verify(&whitelist_proof.proof, &presale.merkle_root, leaf) {
+		return Err(PresaleError::InvalidWhitelistProof.into());
+	}
+
+	client.whitelisted_rounds = client.whitelisted_rounds | whitelist_proof.rounds;
+	client.allocation_cap = lower_cap(client.allocation_cap, whitelist_proof.allocation_cap);
+	client.serialize(&mut *client_account.data.borrow_mut())?;
+	Ok(())
+}
+
+/// A proof never raises a cap set by AddToWhitelist, the stricter cap wins and 0 means no cap
+fn lower_cap(current : u64, proof : u64) -> u64 {
+	match (current, proof) {
+		(0, cap) | (cap, 0) => cap,
+		(current, proof) => current.min(proof),
+	}
+}
+
+/// Allocates the client PDA of `owner_account` and writes its initial state, `payer_account` funds the rent
+pub fn create_client_account<'a>(
+	program_id : &Pubkey,
+	owner_account : &AccountInfo<'a>,
+	presale_account : &AccountInfo<'a>,
+	client_account : &AccountInfo<'a>,
+	payer_account : &AccountInfo<'a>,
+	rent_info : &AccountInfo<'a>,
+	system_account : &AccountInfo<'a>,
+	)->ProgramResult{
+	let bump = assert_derivation(
+		program_id,
+		client_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*owner_account.key).as_ref(),
+		],
+	)?;
+
+	create_or_allocate_account_raw(
+		*program_id,
+		client_account,
+		rent_info,
+		system_account,
+		payer_account,
+		CLIENT_DATA_SIZE,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			(*owner_account.key).as_ref(),
+			&[bump],
+		],
+	)?;
+
+	let client = ClientData{
+		account_type : AccountType::Client,
+		version : CLIENT_DATA_VERSION,
+		owner : *owner_account.key,
+		presale : *presale_account.key,
+		amount : 0,
+		round_amounts : [0; MAX_ROUNDS],
+		whitelisted_rounds : 0,
+		claimed : 0,
+		allocation_cap : 0,
+		paid : [0; MAX_PAYMENT_MINTS],
+		excess_refunded : 0,
+		buy_count : 0,
+		last_buy_slot : 0,
+		whitelist_revoked : false,
+		reserved : [0; CLIENT_DATA_RESERVED],
+	};
+	client.serialize(&mut *client_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,71 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,load_member_client,create_client_account,Role},
+	utils::{assert_owned_by,assert_signer,assert_role,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+/// Clears the whitelist of every (member, client) account pair that follows the fixed accounts,
+/// the members can't register again with their proof until AddToWhitelist lists them.
+/// Members without a client account get one paid by the payer, so an unused proof can be revoked too
+pub fn remove_from_whitelist(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("Processing RemoveFromWhitelist");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let payer_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let rent_info = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account,program_id)?;
+
+	let presale=PresaleData::from_account_info(presale_account)?;
+
+	assert_role(&presale, authority_account, Role::WhitelistManager)?;
+	assert_signer(payer_account)?;
+
+	if account_iter.len() == 0 || account_iter.len() % 2 != 0 {
+		return Err(ProgramError::NotEnoughAccountKeys);
+	}
+
+	while let Some(member_account) = account_iter.next() {
+		let client_account = next_account_info(account_iter)?;
+
+		if client_account.data_is_empty() {
+			create_client_account(
+				program_id,
+				member_account,
+				presale_account,
+				client_account,
+				payer_account,
+				rent_info,
+				system_account,
+			)?;
+		}
+
+		let mut client = load_member_client(program_id,presale_account,member_account,client_account)?;
+		client.whitelisted_rounds = 0;
+		client.whitelist_revoked = true;
+		client.serialize(&mut *client_account.data.borrow_mut())?;
+	}
+
+	Ok(())
+}
\ No newline at end of file
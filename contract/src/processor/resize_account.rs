@@ -0,0 +1,81 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,PRESALE_DATA_SIZE,CLIENT_DATA_SIZE},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation,sol_transfer,SolTransferParams},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,rent::Rent,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct ResizeAccountArgs {
+	/// never below the size of the current layout
+	pub new_size : u64,
+}
+
+/// Grows or shrinks the presale account, or one of its client accounts, the payer funds the extra rent
+pub fn resize_account(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : ResizeAccountArgs,
+	)->ProgramResult{
+	msg!("+ Processing ResizeAccount");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let payer_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let account = next_account_info(account_iter)?;
+	let system_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+	assert_owned_by(account, program_id)?;
+	assert_signer(payer_account)?;
+
+	let presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, authority_account)?;
+
+	let min_size = if account.key == presale_account.key {
+		PRESALE_DATA_SIZE
+	} else {
+		let client = ClientData::from_account_info(account)?;
+		if client.presale != *presale_account.key {
+			return Err(PresaleError::NotMatchPresale.into());
+		}
+		CLIENT_DATA_SIZE
+	};
+
+	let new_size = args.new_size as usize;
+	if new_size < min_size {
+		return Err(PresaleError::InvalidAmount.into());
+	}
+
+	let rent = Rent::get()?;
+	let required_lamports = rent.minimum_balance(new_size).saturating_sub(account.lamports());
+	if required_lamports > 0 {
+		sol_transfer(SolTransferParams{
+			source : payer_account.clone(),
+			destination : account.clone(),
+			amount : required_lamports,
+			source_signer_seeds : &[],
+			system_program : system_account.clone(),
+		})?;
+	}
+
+	account.realloc(new_size, true)?;
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,BuyLimits},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct SetBuyLimitsArgs {
+	pub buy_limits : BuyLimits,
+}
+
+pub fn set_buy_limits(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : SetBuyLimitsArgs,
+	)->ProgramResult{
+	msg!("+ Processing SetBuyLimits");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, authority_account)?;
+
+	presale.buy_limits = args.buy_limits;
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
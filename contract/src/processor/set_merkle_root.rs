@@ -0,0 +1,49 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,Role},
+	utils::{assert_owned_by,assert_signer,assert_role,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct SetMerkleRootArgs {
+	/// all zeros turns the merkle whitelist off
+	pub merkle_root : [u8; 32],
+}
+
+pub fn set_merkle_root(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : SetMerkleRootArgs,
+	)->ProgramResult{
+	msg!("+ Processing SetMerkleRoot");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_role(&presale, authority_account, Role::WhitelistManager)?;
+
+	presale.merkle_root = args.merkle_root;
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,54 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,Role},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
+	PRESALE,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct SetRoleArgs {
+	pub role : Role,
+	/// default revokes the role, handing it back to the authority
+	pub key : Pubkey,
+}
+
+pub fn set_role(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : SetRoleArgs,
+	)->ProgramResult{
+	msg!("+ Processing SetRole");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+
+	assert_owned_by(presale_account, program_id)?;
+
+	let mut presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_authority(&presale, authority_account)?;
+
+	match args.role {
+		Role::WhitelistManager => presale.whitelist_manager = args.key,
+		Role::Pauser => presale.pauser = args.key,
+		Role::Treasurer => presale.treasurer = args.key,
+	}
+	presale.serialize(&mut *presale_account.data.borrow_mut())?;
+	Ok(())
+}
\ No newline at end of file
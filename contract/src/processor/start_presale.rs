@@ -1,7 +1,7 @@
 use crate::{
 	errors::PresaleError,
-	processor::{PresaleData,ClientData},
-	utils::{assert_owned_by,assert_signer,assert_derivation},
+	processor::{PresaleData,ClientData,PresaleStatus,Role},
+	utils::{assert_owned_by,assert_signer,assert_role,assert_derivation},
 	PRESALE,
 };
 
@@ -30,18 +30,19 @@ pub fn start_presale(
 	let presale_account = next_account_info(account_iter)?;
 
 	assert_owned_by(presale_account, program_id)?;
-	assert_signer(authority_account)?;
 
 	let mut presale = PresaleData::from_account_info(presale_account)?;
 
-	if presale.authority != *authority_account.key {
-		return Err(PresaleError::InvalidAuthority.into());
-	}
+	assert_role(&presale, authority_account, Role::Pauser)?;
 
 	if presale.is_active ==true {
 		return Err(PresaleError::AlreadyStarted.into());
 	}
 
+	if presale.status != PresaleStatus::Pending {
+		return Err(PresaleError::AlreadyFinalized.into());
+	}
+
 	presale.is_active=true;
 	presale.serialize(&mut *presale_account.data.borrow_mut())?;
 	Ok(())
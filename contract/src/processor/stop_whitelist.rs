@@ -1,7 +1,7 @@
 use crate::{
 	errors::PresaleError,
 	processor::{PresaleData,ClientData},
-	utils::{assert_owned_by,assert_signer,assert_derivation},
+	utils::{assert_owned_by,assert_signer,assert_authority,assert_derivation},
 	PRESALE,
 };
 
@@ -30,13 +30,10 @@ pub fn stop_whitelist(
 	let presale_account = next_account_info(account_iter)?;
 
 	assert_owned_by(presale_account, program_id)?;
-	assert_signer(authority_account)?;
 
 	let mut presale = PresaleData::from_account_info(presale_account)?;
 
-	if presale.authority != *authority_account.key {
-		return Err(PresaleError::InvalidAuthority.into());
-	}
+	assert_authority(&presale, authority_account)?;
 
 	if presale.is_whitelist == false {
 		return Err(PresaleError::AlreadyStopped.into());
@@ -0,0 +1,170 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,Role},
+	utils::{
+		assert_owned_by,assert_signer,assert_role,assert_derivation,
+		spl_token_transfer,TokenTransferParams,
+		sol_transfer,SolTransferParams,sol_vault_balance,
+	},
+	PRESALE,VAULT,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		system_program,
+		sysvar::{clock::Clock,Sysvar},
+	},
+	spl_token::state::Account,
+};
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct WithdrawFundsArgs {
+	pub amount : u64,
+}
+
+pub fn withdraw_funds(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	args : WithdrawFundsArgs,
+	)->ProgramResult{
+	msg!("+ Processing WithdrawFunds");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let destination_token_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let raise_vault_account = next_account_info(account_iter)?;
+	let mint_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+	let system_account = account_iter.next();
+
+	assert_owned_by(presale_account,program_id)?;
+
+	let presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_role(&presale, authority_account, Role::Treasurer)?;
+
+	presale.assert_succeeded()?;
+
+	if args.amount == 0 {
+		return Err(PresaleError::InvalidAmount.into());
+	}
+
+	let mint_index = presale.payment_mint_index(mint_account.key).ok_or(PresaleError::NotMatchTokenAddress)?;
+
+	transfer_from_raise_vault(
+		program_id,
+		&presale,
+		presale_account,
+		mint_account.key,
+		vault_authority,
+		raise_vault_account,
+		destination_token_account,
+		token_program,
+		system_account,
+		args.amount,
+		presale.reserved_for_refunds(mint_index)?,
+	)
+}
+
+/// Pays `amount` of `mint` out of its escrow, as lamports when the presale raises native SOL;
+/// `reserved` has to stay in the vault afterwards
+pub fn transfer_from_raise_vault<'a>(
+	program_id : &Pubkey,
+	presale : &PresaleData,
+	presale_account : &AccountInfo<'a>,
+	mint : &Pubkey,
+	vault_authority : &AccountInfo<'a>,
+	raise_vault_account : &AccountInfo<'a>,
+	destination_account : &AccountInfo<'a>,
+	token_program : &AccountInfo<'a>,
+	system_account : Option<&AccountInfo<'a>>,
+	amount : u64,
+	reserved : u64,
+	)->ProgramResult{
+	let required = amount.checked_add(reserved).ok_or(PresaleError::MathOverflow)?;
+
+	let vault_bump = assert_derivation(
+		program_id,
+		vault_authority,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	let raise_vault_bump = assert_derivation(
+		program_id,
+		raise_vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			mint.as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	if presale.is_native() && *mint == presale.token_being_raised {
+		assert_owned_by(raise_vault_account,&system_program::id())?;
+
+		if sol_vault_balance(raise_vault_account)? < required {
+			return Err(PresaleError::BalanceTooLow.into());
+		}
+
+		// the lamport vault is its own signer
+		return sol_transfer(SolTransferParams{
+			source : raise_vault_account.clone(),
+			destination : destination_account.clone(),
+			amount : amount,
+			source_signer_seeds : &[
+				PRESALE.as_bytes(),
+				program_id.as_ref(),
+				(*presale_account.key).as_ref(),
+				mint.as_ref(),
+				VAULT.as_bytes(),
+				&[raise_vault_bump],
+			],
+			system_program : system_account.ok_or(ProgramError::NotEnoughAccountKeys)?.clone(),
+		});
+	}
+
+	assert_owned_by(destination_account,&spl_token::id())?;
+	assert_owned_by(raise_vault_account,&spl_token::id())?;
+
+	if *token_program.key != spl_token::id() {
+		return Err(PresaleError::InvalidTokenProgram.into());
+	}
+
+	let vault : Account = Account::unpack_from_slice(&raise_vault_account.data.borrow())?;
+	if vault.amount < required {
+		return Err(PresaleError::BalanceTooLow.into());
+	}
+
+	spl_token_transfer(TokenTransferParams{
+		source : raise_vault_account.clone(),
+		destination : destination_account.clone(),
+		authority : vault_authority.clone(),
+		authority_signer_seeds : &[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+			&[vault_bump],
+		],
+		token_program : token_program.clone(),
+		amount : amount,
+	})
+}
\ No newline at end of file
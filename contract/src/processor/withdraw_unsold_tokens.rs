@@ -0,0 +1,112 @@
+use crate::{
+	errors::PresaleError,
+	processor::{PresaleData,ClientData,PresaleStatus,Role},
+	utils::{
+		assert_owned_by,assert_signer,assert_role,assert_derivation,
+		spl_token_transfer,TokenTransferParams,
+		Rounding,
+	},
+	PRESALE,VAULT,
+};
+
+use {
+	borsh::{BorshDeserialize,BorshSerialize},
+	solana_program::{
+		account_info::{next_account_info,AccountInfo},
+		entrypoint::ProgramResult,
+		msg,
+		program::invoke_signed,
+		program_error::ProgramError,
+		program_pack::Pack,
+		pubkey::Pubkey,
+		system_instruction,
+		sysvar::{clock::Clock,Sysvar},
+	},
+	spl_token::state::Account,
+};
+
+pub fn withdraw_unsold_tokens(
+	program_id : &Pubkey,
+	accounts : &[AccountInfo],
+	)->ProgramResult{
+	msg!("+ Processing WithdrawUnsoldTokens");
+	let account_iter = &mut accounts.iter();
+	let authority_account = next_account_info(account_iter)?;
+	let destination_token_account = next_account_info(account_iter)?;
+	let presale_account = next_account_info(account_iter)?;
+	let vault_authority = next_account_info(account_iter)?;
+	let sale_vault_account = next_account_info(account_iter)?;
+	let token_program = next_account_info(account_iter)?;
+
+	assert_owned_by(destination_token_account,&spl_token::id())?;
+	assert_owned_by(sale_vault_account,&spl_token::id())?;
+	assert_owned_by(presale_account,program_id)?;
+
+	if *token_program.key != spl_token::id() {
+		return Err(PresaleError::InvalidTokenProgram.into());
+	}
+
+	let presale = PresaleData::from_account_info(presale_account)?;
+
+	assert_role(&presale, authority_account, Role::Treasurer)?;
+
+	let vault_bump = assert_derivation(
+		program_id,
+		vault_authority,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	assert_derivation(
+		program_id,
+		sale_vault_account,
+		&[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			presale.token_for_sale.as_ref(),
+			VAULT.as_bytes(),
+		],
+	)?;
+
+	if presale.status == PresaleStatus::Pending {
+		return Err(PresaleError::NotFinalized.into());
+	}
+
+	let vault : Account = Account::unpack_from_slice(&sale_vault_account.data.borrow())?;
+
+	// tokens sold but not claimed yet stay in the vault, unless the sale failed and buyers get refunds instead
+	let owed = if presale.status == PresaleStatus::Failed {
+		0
+	} else {
+		let allocated = presale.allocated_per_round(&presale.raised_per_round(), Rounding::Up)?;
+		let sold = presale.total_sale_amount(&allocated, Rounding::Up)?;
+		sold.saturating_sub(presale.total_claimed)
+	};
+
+	let unsold = vault.amount.saturating_sub(owed);
+	if unsold == 0 {
+		return Err(PresaleError::BalanceTooLow.into());
+	}
+
+	spl_token_transfer(TokenTransferParams{
+		source : sale_vault_account.clone(),
+		destination : destination_token_account.clone(),
+		authority : vault_authority.clone(),
+		authority_signer_seeds : &[
+			PRESALE.as_bytes(),
+			program_id.as_ref(),
+			(*presale_account.key).as_ref(),
+			VAULT.as_bytes(),
+			&[vault_bump],
+		],
+		token_program : token_program.clone(),
+		amount : unsold,
+	})?;
+
+	Ok(())
+}
\ No newline at end of file
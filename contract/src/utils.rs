@@ -1,7 +1,10 @@
 use solana_program::program_pack::IsInitialized;
 
 use {
-    crate::errors::PresaleError,
+    crate::{
+        errors::PresaleError,
+        processor::{PresaleData,Role},
+    },
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
@@ -44,6 +47,104 @@ pub fn assert_derivation(
     Ok(bump)
 }
 
+/// Single place for role checks, `account` must sign and hold `role` on the presale
+pub fn assert_role(presale : &PresaleData, account : &AccountInfo, role : Role) -> ProgramResult {
+    assert_signer(account)?;
+    if presale.role_key(role) != *account.key {
+        return Err(PresaleError::InvalidAuthority.into());
+    }
+    Ok(())
+}
+
+/// Same for the instructions only the authority itself may run, `account` must sign and be the authority
+pub fn assert_authority(presale : &PresaleData, account : &AccountInfo) -> ProgramResult {
+    assert_signer(account)?;
+    if presale.authority != *account.key {
+        return Err(PresaleError::InvalidAuthority.into());
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Converts `amount` base units at `from_decimals` into base units at `to_decimals`,
+/// multiplied by `numerator / denominator`
+pub fn convert_amount(
+    amount : u64,
+    numerator : u64,
+    denominator : u64,
+    from_decimals : u8,
+    to_decimals : u8,
+    rounding : Rounding,
+    ) -> Result<u64, ProgramError> {
+    let overflow = || -> ProgramError { PresaleError::MathOverflow.into() };
+    let scale = |decimals : u8| 10u128.checked_pow(decimals as u32).ok_or_else(overflow);
+
+    let num = (amount as u128)
+        .checked_mul(numerator as u128).ok_or_else(overflow)?
+        .checked_mul(scale(to_decimals)?).ok_or_else(overflow)?;
+    let den = (denominator as u128)
+        .checked_mul(scale(from_decimals)?).ok_or_else(overflow)?;
+    if den == 0 {
+        return Err(PresaleError::InvalidPrice.into());
+    }
+
+    let result = match rounding {
+        Rounding::Down => num / den,
+        Rounding::Up => (num + den - 1) / den,
+    };
+    result.try_into().map_err(|_| overflow())
+}
+
+/// Create account almost from scratch, lifted from
+/// https://github.com/solana-labs/solana-program-library/blob/7d4873c61721aca25464d42cc5ef651a7923ca79/associated-token-account/program/src/processor.rs#L51-L98
+#[inline(always)]
+pub fn create_or_allocate_account_raw<'a>(
+    program_id : Pubkey,
+    new_account_info : &AccountInfo<'a>,
+    rent_sysvar_info : &AccountInfo<'a>,
+    system_program_info : &AccountInfo<'a>,
+    payer_info : &AccountInfo<'a>,
+    size : usize,
+    signer_seeds : &[&[u8]],
+    ) -> ProgramResult {
+    let rent = &Rent::from_account_info(rent_sysvar_info)?;
+    let required_lamports = rent
+        .minimum_balance(size)
+        .max(1)
+        .saturating_sub(new_account_info.lamports());
+
+    if required_lamports > 0 {
+        msg!("Transfer {} lamports to the new account", required_lamports);
+        invoke(
+            &system_instruction::transfer(payer_info.key, new_account_info.key, required_lamports),
+            &[payer_info.clone(), new_account_info.clone(), system_program_info.clone()],
+        )?;
+    }
+
+    let accounts = &[new_account_info.clone(), system_program_info.clone()];
+
+    msg!("Allocate space for the account");
+    invoke_signed(
+        &system_instruction::allocate(new_account_info.key, size.try_into().unwrap()),
+        accounts,
+        &[signer_seeds],
+    )?;
+
+    msg!("Assign the account to the owning program");
+    invoke_signed(
+        &system_instruction::assign(new_account_info.key, &program_id),
+        accounts,
+        &[signer_seeds],
+    )?;
+
+    Ok(())
+}
+
 ///TokenTransferParams
 pub struct TokenTransferParams<'a: 'b, 'b> {
     /// source
@@ -71,57 +172,132 @@ pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> ProgramResult
         authority_signer_seeds,
     } = params;
 
-    let result = invoke_signed(
-        &spl_token::instruction::transfer(
-            token_program.key,
-            source.key,
-            destination.key,
-            authority.key,
-            &[],
-            amount,
-        )?,
-        &[source, destination, authority, token_program],
-        &[authority_signer_seeds],
-    );
+    let instruction = spl_token::instruction::transfer(
+        token_program.key,
+        source.key,
+        destination.key,
+        authority.key,
+        &[],
+        amount,
+    )?;
+    let accounts = &[source, destination, authority, token_program];
+    // empty seeds mean the authority signed the transaction itself
+    let result = if authority_signer_seeds.is_empty() {
+        invoke(&instruction, accounts)
+    } else {
+        invoke_signed(&instruction, accounts, &[authority_signer_seeds])
+    };
 
     result.map_err(|_| PresaleError::TokenTransferFailed.into())
 }
 
-///TokenTransferParams
-pub struct TokenTransferParamsWithoutSeed<'a> {
+///TokenInitAccountParams
+pub struct TokenInitAccountParams<'a> {
+    /// account
+    pub account: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// owner
+    pub owner: AccountInfo<'a>,
+    /// rent
+    pub rent: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+#[inline(always)]
+pub fn spl_token_init_account(params: TokenInitAccountParams<'_>) -> ProgramResult {
+    let TokenInitAccountParams {
+        account,
+        mint,
+        owner,
+        rent,
+        token_program,
+    } = params;
+
+    invoke(
+        &spl_token::instruction::initialize_account(
+            token_program.key,
+            account.key,
+            mint.key,
+            owner.key,
+        )?,
+        &[account, mint, owner, rent, token_program],
+    )
+}
+
+///SolTransferParams
+pub struct SolTransferParams<'a: 'b, 'b> {
     /// source
     pub source: AccountInfo<'a>,
     /// destination
     pub destination: AccountInfo<'a>,
     /// amount
     pub amount: u64,
-    /// authority
-    pub authority: AccountInfo<'a>,
-    /// token_program
-    pub token_program: AccountInfo<'a>,
+    /// source_signer_seeds, empty when the source signs the transaction itself
+    pub source_signer_seeds: &'b [&'b [u8]],
+    /// system_program
+    pub system_program: AccountInfo<'a>,
 }
 
 #[inline(always)]
-pub fn spl_token_transfer_without_seed(params: TokenTransferParamsWithoutSeed<'_>) -> ProgramResult {
-    let TokenTransferParamsWithoutSeed {
+pub fn sol_transfer(params: SolTransferParams<'_, '_>) -> ProgramResult {
+    let SolTransferParams {
         source,
         destination,
-        authority,
-        token_program,
         amount,
+        source_signer_seeds,
+        system_program,
     } = params;
 
-    let result = invoke(
-        &spl_token::instruction::transfer(
-            token_program.key,
-            source.key,
-            destination.key,
-            authority.key,
-            &[],
-            amount,
-        )?,
-        &[source, destination ,authority, token_program],
-    );
+    let instruction = system_instruction::transfer(source.key, destination.key, amount);
+    let accounts = &[source, destination, system_program];
+    let result = if source_signer_seeds.is_empty() {
+        invoke(&instruction, accounts)
+    } else {
+        invoke_signed(&instruction, accounts, &[source_signer_seeds])
+    };
 
     result.map_err(|_| PresaleError::TokenTransferFailed.into())
+}
+
+/// Lamports a system owned vault can pay out while staying rent exempt
+pub fn sol_vault_balance(vault : &AccountInfo) -> Result<u64, ProgramError> {
+    let rent = Rent::get()?;
+    Ok(vault.lamports().saturating_sub(rent.minimum_balance(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(e : PresaleError) -> ProgramError {
+        e.into()
+    }
+
+    #[test]
+    fn convert_amount_rounds_down_and_up() {
+        assert_eq!(convert_amount(10, 1, 3, 0, 0, Rounding::Down), Ok(3));
+        assert_eq!(convert_amount(10, 1, 3, 0, 0, Rounding::Up), Ok(4));
+        assert_eq!(convert_amount(9, 1, 3, 0, 0, Rounding::Down), Ok(3));
+        assert_eq!(convert_amount(9, 1, 3, 0, 0, Rounding::Up), Ok(3));
+    }
+
+    #[test]
+    fn convert_amount_between_decimals() {
+        // 1.5 of a 6 decimal mint at 2 per whole token into a 9 decimal mint
+        assert_eq!(convert_amount(1_500_000, 2, 1, 6, 9, Rounding::Down), Ok(3_000_000_000));
+        // the last 3 digits don't exist at 6 decimals
+        assert_eq!(convert_amount(1_000_000_001, 1, 1, 9, 6, Rounding::Down), Ok(1_000_000));
+        assert_eq!(convert_amount(1_000_000_001, 1, 1, 9, 6, Rounding::Up), Ok(1_000_001));
+        assert_eq!(convert_amount(1_000_000_000, 1, 4, 9, 6, Rounding::Down), Ok(250_000));
+    }
+
+    #[test]
+    fn convert_amount_rejects_overflow_and_zero_denominator() {
+        assert_eq!(convert_amount(u64::MAX, u64::MAX, 1, 0, 0, Rounding::Down), Err(error(PresaleError::MathOverflow)));
+        assert_eq!(convert_amount(u64::MAX, u64::MAX, 1, 0, 9, Rounding::Down), Err(error(PresaleError::MathOverflow)));
+        assert_eq!(convert_amount(1, 1, 1, 0, 40, Rounding::Down), Err(error(PresaleError::MathOverflow)));
+        assert_eq!(convert_amount(1, 1, 0, 0, 0, Rounding::Down), Err(error(PresaleError::InvalidPrice)));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,377 @@
+// runs against the BPF build, `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::{
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_instruction, system_program, sysvar,
+    },
+    solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    },
+    spl_auction::{
+        errors::PresaleError,
+        instruction::{AddPaymentMintArgs, BuyArgs, InitPresaleArgs, PresaleInstruction, RegisterClientArgs},
+        oracle::{PRICE_ACCOUNT_MIN_SIZE, PRICE_ACCOUNT_TYPE, PRICE_MAGIC, PRICE_STATUS_TRADING},
+        processor::{process_instruction, BuyLimits, ClientData, OracleConfig, PresaleData, Vesting},
+        PRESALE, VAULT,
+    },
+};
+
+const SALE_DECIMALS : u8 = 9;
+const RAISE_DECIMALS : u8 = 6;
+const PAYMENT_DECIMALS : u8 = 9;
+const CURRENT_SLOT : u64 = 1_000;
+const MAX_STALENESS_SLOTS : u64 = 25;
+const MAX_CONFIDENCE_BPS : u64 = 100;
+
+/// A Pyth v2 price account, only the fields the program reads are filled in
+fn price_account(owner : Pubkey, price : i64, expo : i32, conf : u64, pub_slot : u64) -> Account {
+    let mut data = vec![0u8; PRICE_ACCOUNT_MIN_SIZE];
+    data[0..4].copy_from_slice(&PRICE_MAGIC.to_le_bytes());
+    data[8..12].copy_from_slice(&PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&PRICE_STATUS_TRADING.to_le_bytes());
+    data[232..240].copy_from_slice(&pub_slot.to_le_bytes());
+    Account {
+        lamports : 1_000_000_000,
+        data,
+        owner,
+        executable : false,
+        rent_epoch : 0,
+    }
+}
+
+fn vault(program_id : &Pubkey, presale : &Pubkey, mint : &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[PRESALE.as_bytes(), program_id.as_ref(), presale.as_ref(), mint.as_ref(), VAULT.as_bytes()],
+        program_id,
+    ).0
+}
+
+struct Setup {
+    context : ProgramTestContext,
+    program_id : Pubkey,
+    oracle_program : Pubkey,
+    feed : Pubkey,
+    presale : Pubkey,
+    payment_mint : Pubkey,
+    payment_vault : Pubkey,
+    bidder : Keypair,
+    bidder_token_account : Pubkey,
+    client : Pubkey,
+}
+
+impl Setup {
+    async fn process(&mut self, instructions : &[Instruction], signers : &[&Keypair]) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.context.banks_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await.map_err(|e| e.unwrap())
+    }
+
+    async fn create_mint(&mut self, decimals : u8) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, decimals).unwrap(),
+            ],
+            &[&mint],
+        ).await.unwrap();
+        mint.pubkey()
+    }
+
+    fn set_feed(&mut self, key : Pubkey, account : Account) {
+        self.context.set_account(&key, &account.into());
+    }
+
+    fn buy(&self, amount : u64, feed : Pubkey) -> Instruction {
+        Instruction {
+            program_id : self.program_id,
+            accounts : vec![
+                AccountMeta::new(self.bidder.pubkey(), true),
+                AccountMeta::new(self.bidder_token_account, false),
+                AccountMeta::new(self.payment_vault, false),
+                AccountMeta::new_readonly(self.bidder.pubkey(), true),
+                AccountMeta::new(self.presale, false),
+                AccountMeta::new(self.client, false),
+                AccountMeta::new_readonly(self.payment_mint, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(feed, false),
+            ],
+            data : PresaleInstruction::Buy(BuyArgs{ amount, allow_partial : false }).try_to_vec().unwrap(),
+        }
+    }
+
+    async fn buy_as_bidder(&mut self, amount : u64, feed : Pubkey) -> Result<(), TransactionError> {
+        let instruction = self.buy(amount, feed);
+        let bidder = self.bidder.insecure_clone();
+        self.process(&[instruction], &[&bidder]).await
+    }
+
+    async fn client_data(&mut self) -> ClientData {
+        let account = self.context.banks_client.get_account(self.client).await.unwrap().unwrap();
+        ClientData::deserialize(&mut &account.data[..]).unwrap()
+    }
+}
+
+async fn setup() -> Setup {
+    setup_with_limits(BuyLimits::default()).await
+}
+
+/// Presale raising a 6 decimals token that also accepts a 9 decimals mint priced by a feed
+async fn setup_with_limits(buy_limits : BuyLimits) -> Setup {
+    let program_id = Pubkey::new_unique();
+    let oracle_program = Pubkey::new_unique();
+    let feed = Pubkey::new_unique();
+    let program_test = ProgramTest::new("spl_auction", program_id, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    context.warp_to_slot(CURRENT_SLOT).unwrap();
+
+    let mut setup = Setup {
+        context,
+        program_id,
+        oracle_program,
+        feed,
+        presale : Pubkey::default(),
+        payment_mint : Pubkey::default(),
+        payment_vault : Pubkey::default(),
+        bidder : Keypair::new(),
+        bidder_token_account : Pubkey::default(),
+        client : Pubkey::default(),
+    };
+    // 1.5 raised tokens per payment token
+    setup.set_feed(feed, price_account(oracle_program, 150_000_000, -8, 100_000, CURRENT_SLOT));
+
+    let authority = setup.context.payer.pubkey();
+    let sale_mint = setup.create_mint(SALE_DECIMALS).await;
+    let raise_mint = setup.create_mint(RAISE_DECIMALS).await;
+    let payment_mint = setup.create_mint(PAYMENT_DECIMALS).await;
+
+    let presale = Pubkey::find_program_address(
+        &[PRESALE.as_bytes(), program_id.as_ref(), authority.as_ref(), sale_mint.as_ref()],
+        &program_id,
+    ).0;
+    let vault_authority = Pubkey::find_program_address(
+        &[PRESALE.as_bytes(), program_id.as_ref(), presale.as_ref(), VAULT.as_bytes()],
+        &program_id,
+    ).0;
+
+    let init_presale = Instruction {
+        program_id,
+        accounts : vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(presale, false),
+            AccountMeta::new_readonly(sale_mint, false),
+            AccountMeta::new_readonly(raise_mint, false),
+            AccountMeta::new_readonly(vault_authority, false),
+            AccountMeta::new(vault(&program_id, &presale, &sale_mint), false),
+            AccountMeta::new(vault(&program_id, &presale, &raise_mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data : PresaleInstruction::InitPresale(InitPresaleArgs{
+            min_allocation : 0,
+            max_allocation : 100_000_000,
+            hardcap : 1_000_000_000,
+            softcap : 0,
+            price_numerator : 10,
+            price_denominator : 1,
+            vesting : Vesting::default(),
+            start_ts : 1,
+            end_ts : 0,
+            whitelist_end_ts : 0,
+            fair_launch : false,
+            buy_limits,
+        }).try_to_vec().unwrap(),
+    };
+
+    let payment_vault = vault(&program_id, &presale, &payment_mint);
+    let add_payment_mint = Instruction {
+        program_id,
+        accounts : vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new(presale, false),
+            AccountMeta::new_readonly(payment_mint, false),
+            AccountMeta::new_readonly(vault_authority, false),
+            AccountMeta::new(payment_vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data : PresaleInstruction::AddPaymentMint(AddPaymentMintArgs{
+            rate_numerator : 0,
+            rate_denominator : 0,
+            oracle : Some(OracleConfig{
+                feed,
+                program : oracle_program,
+                max_staleness_slots : MAX_STALENESS_SLOTS,
+                max_confidence_bps : MAX_CONFIDENCE_BPS,
+            }),
+        }).try_to_vec().unwrap(),
+    };
+
+    let stop_whitelist = Instruction {
+        program_id,
+        accounts : vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(presale, false),
+        ],
+        data : PresaleInstruction::StopWhiteList.try_to_vec().unwrap(),
+    };
+
+    setup.process(&[init_presale, add_payment_mint, stop_whitelist], &[]).await.unwrap();
+
+    let bidder = setup.bidder.pubkey();
+    let bidder_token_account = Keypair::new();
+    let client = Pubkey::find_program_address(
+        &[PRESALE.as_bytes(), program_id.as_ref(), presale.as_ref(), bidder.as_ref()],
+        &program_id,
+    ).0;
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+    let register_client = Instruction {
+        program_id,
+        accounts : vec![
+            AccountMeta::new(bidder, true),
+            AccountMeta::new_readonly(presale, false),
+            AccountMeta::new(client, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data : PresaleInstruction::RegisterClient(RegisterClientArgs{ whitelist_proof : None }).try_to_vec().unwrap(),
+    };
+    let bidder_keypair = setup.bidder.insecure_clone();
+    setup.process(
+        &[
+            system_instruction::transfer(&authority, &bidder, 1_000_000_000),
+            system_instruction::create_account(
+                &authority,
+                &bidder_token_account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &bidder_token_account.pubkey(),
+                &payment_mint,
+                &bidder,
+            ).unwrap(),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &payment_mint,
+                &bidder_token_account.pubkey(),
+                &authority,
+                &[],
+                100_000_000_000,
+            ).unwrap(),
+            register_client,
+        ],
+        &[&bidder_token_account, &bidder_keypair],
+    ).await.unwrap();
+
+    setup.presale = presale;
+    setup.payment_mint = payment_mint;
+    setup.payment_vault = payment_vault;
+    setup.bidder_token_account = bidder_token_account.pubkey();
+    setup.client = client;
+    setup
+}
+
+fn presale_error(error : PresaleError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+#[tokio::test]
+async fn buy_credits_oracle_normalized_amount() {
+    let mut setup = setup().await;
+    let feed = setup.feed;
+
+    // 2 payment tokens at 1.5 are worth 3 raised tokens
+    setup.buy_as_bidder(2_000_000_000, feed).await.unwrap();
+
+    let client = setup.client_data().await;
+    assert_eq!(client.amount, 3_000_000);
+    assert_eq!(client.paid[1], 2_000_000_000);
+
+    let account = setup.context.banks_client.get_account(setup.presale).await.unwrap().unwrap();
+    let presale = PresaleData::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(presale.total_raised, 3_000_000);
+    assert_eq!(presale.payment_mints[1].total_raised, 2_000_000_000);
+}
+
+#[tokio::test]
+async fn buy_rejects_stale_price() {
+    let mut setup = setup().await;
+    let (feed, oracle_program) = (setup.feed, setup.oracle_program);
+    setup.set_feed(feed, price_account(oracle_program, 150_000_000, -8, 100_000, CURRENT_SLOT - MAX_STALENESS_SLOTS - 10));
+
+    assert_eq!(setup.buy_as_bidder(2_000_000_000, feed).await, Err(presale_error(PresaleError::StalePrice)));
+}
+
+#[tokio::test]
+async fn buy_rejects_wide_confidence() {
+    let mut setup = setup().await;
+    let (feed, oracle_program) = (setup.feed, setup.oracle_program);
+    // 2% against a 1% limit
+    setup.set_feed(feed, price_account(oracle_program, 150_000_000, -8, 3_000_000, CURRENT_SLOT));
+
+    assert_eq!(setup.buy_as_bidder(2_000_000_000, feed).await, Err(presale_error(PresaleError::PriceConfidenceTooWide)));
+}
+
+#[tokio::test]
+async fn buy_rejects_foreign_feed() {
+    let mut setup = setup().await;
+    let (feed, oracle_program) = (setup.feed, setup.oracle_program);
+
+    // right key, owned by another program
+    setup.set_feed(feed, price_account(Pubkey::new_unique(), 150_000_000, -8, 100_000, CURRENT_SLOT));
+    assert_eq!(setup.buy_as_bidder(2_000_000_000, feed).await, Err(presale_error(PresaleError::InvalidOracle)));
+
+    // right owner, not the configured feed
+    let other_feed = Pubkey::new_unique();
+    setup.set_feed(other_feed, price_account(oracle_program, 150_000_000, -8, 100_000, CURRENT_SLOT));
+    assert_eq!(setup.buy_as_bidder(2_000_000_000, other_feed).await, Err(presale_error(PresaleError::InvalidOracle)));
+}
+
+#[tokio::test]
+async fn zero_value_buy_keeps_slot_budget() {
+    let mut setup = setup_with_limits(BuyLimits{
+        max_buys_per_client : 0,
+        min_slots_between_buys : 0,
+        max_buys_per_slot : 1,
+    }).await;
+    let feed = setup.feed;
+
+    assert_eq!(setup.buy_as_bidder(0, feed).await, Err(presale_error(PresaleError::InvalidAmount)));
+    // 1 base unit at 1.5 rounds down to nothing of the 6 decimals token
+    assert_eq!(setup.buy_as_bidder(1, feed).await, Err(presale_error(PresaleError::InvalidAmount)));
+
+    setup.buy_as_bidder(2_000_000_000, feed).await.unwrap();
+    assert_eq!(setup.client_data().await.buy_count, 1);
+}